@@ -0,0 +1,101 @@
+use iced::{
+    Element,
+    Length::Fill,
+    widget::{column, scrollable, text},
+};
+use minesweeper::board::{Board, Position};
+
+use crate::solver;
+
+/// What went wrong in a lost game, worked out from the board just before
+/// the fatal move.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub wrong_flags: Vec<Position>,
+    pub fatal: Option<Position>,
+    pub verdict: Verdict,
+    pub cleared: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Verdict {
+    /// A chord or "open the rest" hit a mine because a flag was misplaced.
+    WrongFlag,
+    /// Logic alone proved this cell safe when the mine was clicked.
+    Avoidable { safe_cell: Position },
+    /// No cell was provably safe; the fatal cell had this chance of being a
+    /// mine, if it bordered any number.
+    Guess { probability: Option<f32> },
+}
+
+impl Analysis {
+    pub fn new(before: &Board, after: &Board, fatal: Option<Position>, chorded: bool) -> Self {
+        let wrong_flags = after
+            .cells()
+            .filter(|(_, cell)| cell.flags() > cell.mines())
+            .map(|(position, _)| position)
+            .collect();
+
+        let verdict = if chorded {
+            Verdict::WrongFlag
+        } else if let Some(safe_cell) = solver::find_safe_cell(before) {
+            Verdict::Avoidable { safe_cell }
+        } else {
+            Verdict::Guess {
+                probability: fatal
+                    .and_then(|fatal| solver::mine_probabilities(before).get(&fatal).copied()),
+            }
+        };
+
+        let safe_cells = after.cells().filter(|(_, cell)| !cell.is_mine()).count();
+        let revealed = after
+            .cells()
+            .filter(|(_, cell)| !cell.is_mine() && cell.is_revealed())
+            .count();
+
+        Self {
+            wrong_flags,
+            fatal,
+            verdict,
+            cleared: match safe_cells {
+                0 => 0.0,
+                safe_cells => revealed as f32 / safe_cells as f32,
+            },
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&self) -> Element<'a, Message> {
+        let verdict = match self.verdict {
+            Verdict::WrongFlag => {
+                "The fatal move trusted a misplaced flag, so it could have been avoided.".to_owned()
+            }
+            Verdict::Avoidable { safe_cell } => format!(
+                "Avoidable: the cell at row {}, column {} was provably safe.",
+                safe_cell.row() + 1,
+                safe_cell.column() + 1
+            ),
+            Verdict::Guess {
+                probability: Some(probability),
+            } => format!(
+                "Forced guess: no cell was provably safe and the fatal cell was a mine with {:.0}% probability.",
+                probability * 100.0
+            ),
+            Verdict::Guess { probability: None } => {
+                "Forced guess: no cell was provably safe.".to_owned()
+            }
+        };
+
+        scrollable(
+            column![
+                text("Analysis").size(18),
+                text(verdict).size(14),
+                text!("Board cleared: {:.0}%", self.cleared * 100.0).size(14),
+                text!("Wrong flags: {}", self.wrong_flags.len()).size(14),
+            ]
+            .padding(12)
+            .spacing(8)
+            .width(Fill),
+        )
+        .into()
+    }
+}
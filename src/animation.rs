@@ -0,0 +1,201 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use iced::{
+    Color, Point, Size, Vector,
+    widget::canvas::{Frame, Path},
+};
+use minesweeper::{board::Position, grid::Grid};
+use rand::Rng;
+use web_time::Instant;
+
+/// How long the largest cascades take to open.
+const CASCADE_DURATION: Duration = Duration::from_millis(300);
+/// Small cascades open at this pace rather than being stretched out.
+const MAX_WAVE_DURATION: Duration = Duration::from_millis(40);
+
+const EXPLOSION_DURATION: Duration = Duration::from_millis(700);
+const EXPLOSION_PARTICLES: usize = 40;
+const CONFETTI_DURATION: Duration = Duration::from_millis(1800);
+const CONFETTI_PARTICLES: usize = 120;
+
+/// Opens the cells revealed by one move in waves spreading out from where
+/// the move started, rather than all at once.
+#[derive(Clone, Debug)]
+pub struct Cascade {
+    started_at: Instant,
+    /// How many steps each cell is from the nearest cell the move opened
+    /// directly.
+    waves: HashMap<Position, u32>,
+    last_wave: u32,
+    wave_duration: Duration,
+}
+
+impl Cascade {
+    /// Lays out the waves of cells revealed in the order the flood fill
+    /// reached them, or returns `None` if they would all open in one wave.
+    pub fn new(grid: Grid, revealed: &[Position], now: Instant) -> Option<Self> {
+        let cells: HashSet<_> = revealed.iter().copied().collect();
+        let mut waves = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        for &start in revealed {
+            if waves.contains_key(&start) {
+                continue;
+            }
+
+            waves.insert(start, 0);
+            queue.push_back(start);
+
+            while let Some(position) = queue.pop_front() {
+                let wave = waves[&position] + 1;
+
+                for neighbour in grid.neighbours(position) {
+                    if cells.contains(&neighbour) && !waves.contains_key(&neighbour) {
+                        waves.insert(neighbour, wave);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        let last_wave = waves.values().copied().max().filter(|&wave| wave > 0)?;
+
+        Some(Self {
+            started_at: now,
+            waves,
+            last_wave,
+            wave_duration: (CASCADE_DURATION / last_wave).min(MAX_WAVE_DURATION),
+        })
+    }
+
+    /// The last wave that has opened by `now`.
+    pub fn wave(&self, now: Instant) -> u32 {
+        let elapsed = now.saturating_duration_since(self.started_at);
+
+        (elapsed.as_secs_f32() / self.wave_duration.as_secs_f32()) as u32
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.wave(now) >= self.last_wave
+    }
+
+    /// How many of the cells are still drawn covered at `now`.
+    pub fn hidden(&self, now: Instant) -> usize {
+        let wave = self.wave(now);
+        self.waves.values().filter(|&&at| at > wave).count()
+    }
+
+    /// Whether the cell is still drawn covered once `wave` has opened.
+    pub fn hides(&self, position: Position, wave: u32) -> bool {
+        self.waves.get(&position).is_some_and(|&at| at > wave)
+    }
+}
+
+/// Particles thrown out when the game ends, drawn on top of the board until
+/// they have faded.
+#[derive(Clone, Debug)]
+pub struct Burst {
+    started_at: Instant,
+    duration: Duration,
+    /// Downwards acceleration, in units per second squared.
+    gravity: f32,
+    particles: Vec<Particle>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Particle {
+    origin: Point,
+    velocity: Vector,
+    /// Turns per second, for confetti that flutters as it falls.
+    spin: f32,
+    size: f32,
+    color: Color,
+}
+
+impl Burst {
+    /// Sparks flying from the centre of the mine that went off, in board
+    /// units.
+    pub fn explosion(centre: Point, colors: &[Color], now: Instant) -> Self {
+        let mut rng = rand::rng();
+        let particles = (0..EXPLOSION_PARTICLES)
+            .map(|_| {
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
+                let speed = rng.random_range(1.5..5.0);
+
+                Particle {
+                    origin: centre,
+                    velocity: Vector::new(angle.cos(), angle.sin()) * speed,
+                    spin: 0.0,
+                    size: rng.random_range(0.08..0.2),
+                    color: colors[rng.random_range(0..colors.len())],
+                }
+            })
+            .collect();
+
+        Self {
+            started_at: now,
+            duration: EXPLOSION_DURATION,
+            gravity: 6.0,
+            particles,
+        }
+    }
+
+    /// Confetti falling from above the top edge, with positions in fractions
+    /// of the canvas and sizes in pixels.
+    pub fn confetti(colors: &[Color], now: Instant) -> Self {
+        let mut rng = rand::rng();
+        let particles = (0..CONFETTI_PARTICLES)
+            .map(|_| Particle {
+                origin: Point::new(rng.random_range(0.0..1.0), rng.random_range(-0.6..-0.05)),
+                velocity: Vector::new(rng.random_range(-0.15..0.15), rng.random_range(0.2..0.6)),
+                spin: rng.random_range(-2.0..2.0),
+                size: rng.random_range(5.0..10.0),
+                color: colors[rng.random_range(0..colors.len())],
+            })
+            .collect();
+
+        Self {
+            started_at: now,
+            duration: CONFETTI_DURATION,
+            gravity: 0.3,
+            particles,
+        }
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) >= self.duration
+    }
+
+    /// Draws the particles where they are at `now`. Positions are stretched
+    /// by `scale` to reach frame coordinates, while sizes are taken as they
+    /// are.
+    pub fn draw(&self, frame: &mut Frame, now: Instant, scale: Size) {
+        let t = now.saturating_duration_since(self.started_at).as_secs_f32();
+        // Particles keep their colour for the first half, then fade out.
+        let alpha = (2.0 * (1.0 - t / self.duration.as_secs_f32())).clamp(0.0, 1.0);
+
+        for particle in &self.particles {
+            let position = particle.origin
+                + particle.velocity * t
+                + Vector::new(0.0, self.gravity * t * t / 2.0);
+            let position = Point::new(position.x * scale.width, position.y * scale.height);
+            // Flutter by squashing the piece as it turns.
+            let turn = (particle.spin * t * std::f32::consts::TAU)
+                .cos()
+                .abs()
+                .max(0.2);
+            let size = Size::new(particle.size, particle.size * turn);
+
+            frame.fill(
+                &Path::rectangle(position - Vector::new(size.width, size.height) * 0.5, size),
+                Color {
+                    a: particle.color.a * alpha,
+                    ..particle.color
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use minesweeper::difficulty::Difficulty;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{game_state::SavedGame, storage};
+
+const FILE: &str = "autosave.json";
+const VERSION: u64 = 1;
+
+/// A game left unfinished when the application was closed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Autosave {
+    version: u64,
+    pub difficulty: Difficulty,
+    pub game: SavedGame,
+}
+
+impl Autosave {
+    pub fn new(difficulty: Difficulty, game: SavedGame) -> Self {
+        Self {
+            version: VERSION,
+            difficulty,
+            game,
+        }
+    }
+
+    /// Reads the autosave if there is one. Saves written by another version
+    /// of the format are discarded; migrations from older versions belong
+    /// here once the format changes.
+    pub fn load() -> Option<Self> {
+        let value: Value = storage::load::<Option<Value>>(FILE)?;
+        let version = value.get("version").and_then(Value::as_u64);
+
+        if version != Some(VERSION) {
+            warn!(?version, "discarding autosave with an unsupported version");
+            Self::remove();
+            return None;
+        }
+
+        serde_json::from_value(value)
+            .inspect_err(|error| warn!(%error, "discarding unreadable autosave"))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        storage::save(FILE, self);
+    }
+
+    pub fn remove() {
+        storage::remove(FILE);
+    }
+}
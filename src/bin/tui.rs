@@ -0,0 +1,205 @@
+//! Plays minesweeper in the terminal, on the same rules as the window.
+//!
+//! Usage: `minesweeper-tui [easy|medium|hard|huge] [seed]`
+
+use std::{env, io};
+
+use minesweeper::{
+    board::{Board, CellType, GameStatus, Marking, Position},
+    difficulty::Difficulty,
+    grid::Grid,
+    seed::Seed,
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+const CONTROLS: &str = "arrows/hjkl move  space reveal  f flag  c chord  r restart  q quit";
+
+struct App {
+    difficulty: Difficulty,
+    board: Board,
+    cursor: Position,
+}
+
+impl App {
+    fn new(difficulty: Difficulty, seed: Seed) -> Self {
+        let (width, height, mines) = difficulty.board();
+
+        Self {
+            difficulty,
+            board: Board::new(Grid::new(width, height), mines, seed),
+            cursor: Position::new(height / 2, width / 2),
+        }
+    }
+
+    fn handle(&mut self, code: KeyCode) {
+        if let KeyCode::Char('r') = code {
+            *self = Self::new(self.difficulty, Seed::random());
+            return;
+        }
+
+        if self.board.status() != GameStatus::InProgress {
+            return;
+        }
+
+        let grid = self.board.grid();
+        let movement = match code {
+            KeyCode::Up | KeyCode::Char('k') => Some((-1, 0)),
+            KeyCode::Down | KeyCode::Char('j') => Some((1, 0)),
+            KeyCode::Left | KeyCode::Char('h') => Some((0, -1)),
+            KeyCode::Right | KeyCode::Char('l') => Some((0, 1)),
+            _ => None,
+        };
+
+        if let Some((rows, columns)) = movement {
+            self.cursor = Position::new(
+                (self.cursor.row() + rows).clamp(0, grid.height as i32 - 1) as usize,
+                (self.cursor.column() + columns).clamp(0, grid.width as i32 - 1) as usize,
+            );
+            return;
+        }
+
+        let is_revealed = self
+            .board
+            .get(self.cursor)
+            .is_some_and(|cell| cell.is_revealed());
+
+        match code {
+            KeyCode::Char(' ') | KeyCode::Enter if is_revealed => {
+                self.board.chord(self.cursor, false);
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                self.board.reveal(self.cursor);
+            }
+            KeyCode::Char('f') => {
+                self.board.flag(self.cursor, false);
+            }
+            KeyCode::Char('c') => {
+                self.board.chord(self.cursor, false);
+            }
+            _ => {}
+        }
+
+        match self.board.status() {
+            GameStatus::Lost => self.board.expose_mines(),
+            GameStatus::Won => self.board.flag_remaining_mines(),
+            GameStatus::InProgress => {}
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let grid = self.board.grid();
+
+        let status = match self.board.status() {
+            GameStatus::InProgress => format!("Mines: {}", self.board.mines_remaining()),
+            GameStatus::Won => "You won!".to_owned(),
+            GameStatus::Lost => "Game over".to_owned(),
+        };
+
+        let rows = (0..grid.height).map(|row| {
+            Line::from_iter((0..grid.width).map(|column| {
+                let position = Position::new(row, column);
+                let (glyph, mut style) = self.glyph(position);
+
+                if position == self.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+
+                Span::styled(format!(" {glyph}"), style)
+            }))
+        });
+
+        let lines = [Line::from(status), Line::default()]
+            .into_iter()
+            .chain(rows)
+            .chain([Line::default(), Line::from(CONTROLS).style(Color::DarkGray)]);
+
+        let title = format!(
+            " Minesweeper ({:?}, seed {}) ",
+            self.difficulty,
+            self.board.seed()
+        );
+        frame.render_widget(
+            Paragraph::new(Vec::from_iter(lines)).block(Block::bordered().title(title)),
+            frame.area(),
+        );
+    }
+
+    fn glyph(&self, position: Position) -> (char, Style) {
+        let Some(cell) = self.board.get(position) else {
+            return (' ', Style::default());
+        };
+
+        if !cell.is_revealed() {
+            return match cell.marking() {
+                Marking::Flag => ('F', Style::default().fg(Color::Red)),
+                Marking::QuestionMark => ('?', Style::default().fg(Color::Yellow)),
+                Marking::None => ('·', Style::default().fg(Color::Gray)),
+            };
+        }
+
+        match cell.cell_type() {
+            CellType::Mine { .. } => ('*', Style::default().fg(Color::LightRed)),
+            CellType::NonMine { neighbours: 0 } => (' ', Style::default()),
+            CellType::NonMine { neighbours } => {
+                let color = match neighbours {
+                    1 => Color::LightBlue,
+                    2 => Color::Green,
+                    3 => Color::Red,
+                    4 => Color::Blue,
+                    5 => Color::Magenta,
+                    6 => Color::Cyan,
+                    _ => Color::White,
+                };
+                let digit = char::from_digit(neighbours as u32, 10).unwrap_or('+');
+
+                (
+                    digit,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )
+            }
+        }
+    }
+}
+
+fn run(mut terminal: DefaultTerminal, mut app: App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                code => app.handle(code),
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let difficulty = args
+        .next()
+        .and_then(|name| {
+            Difficulty::ALL
+                .into_iter()
+                .find(|difficulty| format!("{difficulty:?}").eq_ignore_ascii_case(&name))
+        })
+        .unwrap_or_default();
+    let seed = args
+        .next()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(Seed::random);
+
+    let terminal = ratatui::init();
+    let result = run(terminal, App::new(difficulty, seed));
+    ratatui::restore();
+
+    result
+}
@@ -0,0 +1,957 @@
+use std::collections::VecDeque;
+
+use itertools::iproduct;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    grid::{FirstClick, Grid, Neighbours},
+    mask::Mask,
+    seed::Seed,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum CellType {
+    Mine { count: u8 },
+    NonMine { neighbours: usize },
+}
+
+/// A board cell packed into 16 bits: bit 0 marks a mine, bit 1 a revealed
+/// cell, bits 2-3 hold the [`Marking`], bits 4-8 the number of neighbouring
+/// mines, bits 9-10 the mines beyond the first and bits 11-12 the flags
+/// beyond the first. Bit 13 marks a cell cut out of the board by a [`Mask`],
+/// and bits 14-15 hold the lie a liar board's number tells: none, one too
+/// few or one too many. Cells of classic boards fit in the low byte.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cell(u16);
+
+impl Cell {
+    const MINE: u16 = 0b0000_0000_0000_0001;
+    const REVEALED: u16 = 0b0000_0000_0000_0010;
+    const MARKING_SHIFT: u32 = 2;
+    const MARKING_MASK: u16 = 0b0000_0000_0000_1100;
+    const NEIGHBOURS_SHIFT: u32 = 4;
+    const NEIGHBOURS_MASK: u16 = 0b0000_0001_1111_0000;
+    const EXTRA_MINES_SHIFT: u32 = 9;
+    const EXTRA_MINES_MASK: u16 = 0b0000_0110_0000_0000;
+    const EXTRA_FLAGS_SHIFT: u32 = 11;
+    const EXTRA_FLAGS_MASK: u16 = 0b0001_1000_0000_0000;
+    const VOID: u16 = 0b0010_0000_0000_0000;
+    const LIE_SHIFT: u32 = 14;
+    const LIE_MASK: u16 = 0b1100_0000_0000_0000;
+
+    fn add_mine(&mut self) {
+        if self.is_mine() {
+            self.0 += 1 << Self::EXTRA_MINES_SHIFT;
+        } else {
+            self.0 = (self.0 & !Self::NEIGHBOURS_MASK) | Self::MINE;
+        }
+    }
+
+    fn is_void(self) -> bool {
+        self.0 & Self::VOID != 0
+    }
+
+    pub fn is_mine(self) -> bool {
+        self.0 & Self::MINE != 0
+    }
+
+    pub fn mines(self) -> u8 {
+        if self.is_mine() {
+            1 + ((self.0 & Self::EXTRA_MINES_MASK) >> Self::EXTRA_MINES_SHIFT) as u8
+        } else {
+            0
+        }
+    }
+
+    /// The number of flags on the cell; more than one only with several
+    /// mines per cell.
+    pub fn flags(self) -> u8 {
+        if self.marking() == Marking::Flag {
+            1 + ((self.0 & Self::EXTRA_FLAGS_MASK) >> Self::EXTRA_FLAGS_SHIFT) as u8
+        } else {
+            0
+        }
+    }
+
+    fn set_flags(&mut self, flags: u8) {
+        self.set_marking(if flags > 0 {
+            Marking::Flag
+        } else {
+            Marking::None
+        });
+        self.0 = (self.0 & !Self::EXTRA_FLAGS_MASK)
+            | (u16::from(flags.saturating_sub(1)) << Self::EXTRA_FLAGS_SHIFT);
+    }
+
+    pub fn is_revealed(self) -> bool {
+        self.0 & Self::REVEALED != 0
+    }
+
+    pub fn reveal(&mut self) {
+        self.0 |= Self::REVEALED;
+    }
+
+    pub fn marking(self) -> Marking {
+        match (self.0 & Self::MARKING_MASK) >> Self::MARKING_SHIFT {
+            1 => Marking::Flag,
+            2 => Marking::QuestionMark,
+            _ => Marking::None,
+        }
+    }
+
+    pub fn set_marking(&mut self, marking: Marking) {
+        self.0 = (self.0 & !(Self::MARKING_MASK | Self::EXTRA_FLAGS_MASK))
+            | ((marking as u16) << Self::MARKING_SHIFT);
+    }
+
+    pub fn cell_type(self) -> CellType {
+        if self.is_mine() {
+            CellType::Mine {
+                count: self.mines(),
+            }
+        } else {
+            CellType::NonMine {
+                neighbours: ((self.0 & Self::NEIGHBOURS_MASK) >> Self::NEIGHBOURS_SHIFT) as usize,
+            }
+        }
+    }
+
+    /// The number a safe cell shows: its neighbouring mines, give or take
+    /// the lie it was dealt. `None` for mines.
+    pub fn number(self) -> Option<usize> {
+        let CellType::NonMine { neighbours } = self.cell_type() else {
+            return None;
+        };
+
+        Some(match (self.0 & Self::LIE_MASK) >> Self::LIE_SHIFT {
+            1 => neighbours.saturating_sub(1),
+            2 => neighbours + 1,
+            _ => neighbours,
+        })
+    }
+
+    fn tell_lie(&mut self, high: bool) {
+        self.0 = (self.0 & !Self::LIE_MASK) | (if high { 2 } else { 1 } << Self::LIE_SHIFT);
+    }
+
+    /// Turns a mine cell into a safe one with `neighbours` mines around it,
+    /// keeping whether it is revealed.
+    fn defuse(&mut self, neighbours: u8) {
+        self.0 = (self.0 & Self::REVEALED) | (u16::from(neighbours) << Self::NEIGHBOURS_SHIFT);
+    }
+
+    fn remove_neighbouring_mines(&mut self, count: u8) {
+        if !self.is_mine() {
+            self.0 -= u16::from(count) << Self::NEIGHBOURS_SHIFT;
+        }
+    }
+
+    fn add_neighbouring_mine(&mut self) {
+        if !self.is_mine() {
+            self.0 += 1 << Self::NEIGHBOURS_SHIFT;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Marking {
+    #[default]
+    None = 0,
+    Flag = 1,
+    QuestionMark = 2,
+}
+
+impl Marking {
+    pub fn next(self, question_marks: bool) -> Self {
+        match self {
+            Marking::None => Marking::Flag,
+            Marking::Flag if question_marks => Marking::QuestionMark,
+            Marking::Flag | Marking::QuestionMark => Marking::None,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize,
+)]
+pub struct Position {
+    row: i32,
+    column: i32,
+}
+
+impl Position {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row: row as i32,
+            column: column as i32,
+        }
+    }
+
+    pub fn row(&self) -> i32 {
+        self.row
+    }
+
+    pub fn column(&self) -> i32 {
+        self.column
+    }
+
+    pub fn offset(&self, rows: i32, columns: i32) -> Self {
+        Self {
+            row: self.row + rows,
+            column: self.column + columns,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GameStatus {
+    #[default]
+    InProgress,
+    Won,
+    Lost,
+}
+
+/// The cells of a game and the rules for changing them. Mines are placed on
+/// the first reveal, keeping clear of the revealed cell as far as the grid's
+/// [`FirstClick`] asks. Cells are stored row by row.
+#[derive(Clone, Debug)]
+pub struct Board {
+    cells: Vec<Cell>,
+    grid: Grid,
+    mines: usize,
+    seed: Seed,
+    is_generated: bool,
+    flags_placed: usize,
+    cells_revealed: usize,
+    safe_cells_remaining: usize,
+    mine_revealed: bool,
+}
+
+impl Board {
+    pub fn new(grid: Grid, mines: usize, seed: Seed) -> Self {
+        Self {
+            cells: vec![Cell::default(); grid.width * grid.height],
+            grid,
+            mines,
+            seed,
+            is_generated: false,
+            flags_placed: 0,
+            cells_revealed: 0,
+            safe_cells_remaining: (grid.width * grid.height).saturating_sub(mines),
+            mine_revealed: false,
+        }
+    }
+
+    /// A board with its mines already in place rather than dealt from the
+    /// seed on the first reveal.
+    pub fn with_mines(grid: Grid, seed: Seed, mines: &[Position]) -> Self {
+        let mut board = Self::new(grid, mines.len(), seed);
+        board.place_mines(mines);
+        board
+    }
+
+    /// A board made of only the cells inside `mask`.
+    pub fn masked(grid: Grid, mines: usize, seed: Seed, mask: &Mask) -> Self {
+        let mut board = Self::new(grid, mines, seed);
+
+        for (index, cell) in board.cells.iter_mut().enumerate() {
+            if !mask.contains(Position::new(index / grid.width, index % grid.width)) {
+                cell.0 = Cell::VOID;
+            }
+        }
+
+        board.safe_cells_remaining = mask.cell_count().saturating_sub(mines);
+        board
+    }
+
+    /// Rebuilds a board from cells packed row by row, or returns `None` if
+    /// they do not match the grid.
+    pub fn restore(
+        grid: Grid,
+        mines: usize,
+        seed: Seed,
+        cells: Vec<u16>,
+        is_generated: bool,
+    ) -> Option<Self> {
+        if cells.len() != grid.width * grid.height {
+            return None;
+        }
+
+        let mut board = Self::new(grid, mines, seed);
+        board.cells = cells.into_iter().map(Cell).collect();
+        board.is_generated = is_generated;
+        board.flags_placed = board
+            .cells
+            .iter()
+            .map(|cell| usize::from(cell.flags()))
+            .sum();
+        board.cells_revealed = board.cells.iter().filter(|cell| cell.is_revealed()).count();
+        board.mine_revealed = board
+            .cells
+            .iter()
+            .any(|cell| cell.is_mine() && cell.is_revealed());
+        // Before the first reveal there are no mines yet, only cells they
+        // will go into, and cells cut out by a mask hold neither.
+        board.safe_cells_remaining = if is_generated {
+            board
+                .cells()
+                .filter(|(_, cell)| !cell.is_mine() && !cell.is_revealed())
+                .count()
+        } else {
+            board.cells().count().saturating_sub(mines)
+        };
+
+        Some(board)
+    }
+
+    /// The cells packed row by row, the form [`Board::restore`] takes.
+    pub fn packed(&self) -> Vec<u16> {
+        self.cells.iter().map(|cell| cell.0).collect()
+    }
+
+    /// Where the cell at `position` is stored, or `None` if it is off the
+    /// board.
+    fn index(&self, position: Position) -> Option<usize> {
+        let row = usize::try_from(position.row()).ok()?;
+        let column = usize::try_from(position.column()).ok()?;
+
+        (row < self.grid.height && column < self.grid.width)
+            .then_some(row * self.grid.width + column)
+    }
+
+    /// The cell at `position`, or `None` if it is off the board or cut out
+    /// of it.
+    pub fn get(&self, position: Position) -> Option<Cell> {
+        self.index(position)
+            .map(|index| self.cells[index])
+            .filter(|cell| !cell.is_void())
+    }
+
+    fn get_mut(&mut self, position: Position) -> Option<&mut Cell> {
+        self.index(position)
+            .map(|index| &mut self.cells[index])
+            .filter(|cell| !cell.is_void())
+    }
+
+    /// Every cell on the board with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        let width = self.grid.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_void())
+            .map(move |(index, &cell)| (Position::new(index / width, index % width), cell))
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.grid
+    }
+
+    /// The number of mines, which may be fewer than asked for when they did
+    /// not all fit around the first reveal.
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    /// Whether the mines have been placed, which happens on the first reveal.
+    pub fn is_generated(&self) -> bool {
+        self.is_generated
+    }
+
+    pub fn flags_placed(&self) -> usize {
+        self.flags_placed
+    }
+
+    /// Mines left to flag; goes negative when more flags than mines are placed.
+    pub fn mines_remaining(&self) -> isize {
+        self.mines as isize - self.flags_placed as isize
+    }
+
+    /// The cell on the board nearest the middle of the grid, which on a
+    /// masked board may not be the middle itself.
+    pub fn central_cell(&self) -> Position {
+        let middle = Position::new(self.grid.height / 2, self.grid.width / 2);
+
+        self.cells()
+            .map(|(position, _)| position)
+            .min_by_key(|position| {
+                (position.row() - middle.row()).pow(2)
+                    + (position.column() - middle.column()).pow(2)
+            })
+            .unwrap_or(middle)
+    }
+
+    pub fn cells_revealed(&self) -> usize {
+        self.cells_revealed
+    }
+
+    pub fn safe_cells_revealed(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|cell| cell.is_revealed() && !cell.is_mine())
+            .count()
+    }
+
+    pub fn safe_cells_remaining(&self) -> usize {
+        self.safe_cells_remaining
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.mine_revealed {
+            GameStatus::Lost
+        } else if self.is_generated && self.safe_cells_remaining == 0 {
+            GameStatus::Won
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    fn generate(&mut self, starting_position: Position) {
+        // Candidates are enumerated in board order so that a seed always
+        // yields the same layout. Each cell is offered once per mine it can
+        // hold. The seed alone orders them, and the mines go into the first
+        // ones outside the safe area, so the first click only moves the mines
+        // it would have hit.
+        let around_start = self.grid.neighbours(starting_position);
+        let is_safe = |p: &Position| match self.grid.first_click {
+            FirstClick::Anywhere => false,
+            FirstClick::SafeCell => *p == starting_position,
+            FirstClick::Opening => *p == starting_position || around_start.contains(p),
+        };
+
+        let mut candidates: Vec<_> = iproduct!(0..self.grid.height, 0..self.grid.width)
+            .map(|(row, column)| Position::new(row, column))
+            .filter(|p| self.get(*p).is_some())
+            .flat_map(|p| std::iter::repeat_n(p, self.grid.mines_per_cell.into()))
+            .collect();
+        candidates.shuffle(&mut self.seed.rng());
+
+        let mine_positions: Vec<_> = candidates
+            .into_iter()
+            .filter(|p| !is_safe(p))
+            .take(self.mines)
+            .collect();
+
+        self.place_mines(&mine_positions);
+
+        info!(
+            width = self.grid.width,
+            height = self.grid.height,
+            mines = self.mines,
+            seed = %self.seed,
+            start = ?starting_position,
+            "board generated"
+        );
+    }
+
+    /// Puts a mine on each of `positions`, which may repeat a cell to give
+    /// it several, and counts the board as generated.
+    fn place_mines(&mut self, positions: &[Position]) {
+        self.mines = positions.len();
+
+        for &p in positions {
+            if let Some(cell) = self.get_mut(p) {
+                cell.add_mine();
+            }
+
+            for neighbor in self.grid.neighbours(p) {
+                if let Some(cell) = self.get_mut(neighbor) {
+                    cell.add_neighbouring_mine();
+                }
+            }
+        }
+
+        self.safe_cells_remaining = self.cells().filter(|(_, cell)| !cell.is_mine()).count();
+        self.is_generated = true;
+    }
+
+    /// Reveals a cell, placing the mines first if this is the first reveal.
+    /// Cells without neighbouring mines open their neighbours too. Returns
+    /// the cells revealed, in order.
+    pub fn reveal(&mut self, position: Position) -> Vec<Position> {
+        if self.get(position).is_none() {
+            return Vec::new();
+        }
+
+        if !self.is_generated {
+            self.generate(position);
+        }
+
+        self.flood([position])
+    }
+
+    /// Reveals the cells and, through empty cells, the area around them.
+    /// Works through a queue rather than recursing, so that large open areas
+    /// cannot overflow the stack.
+    fn flood(&mut self, positions: impl IntoIterator<Item = Position>) -> Vec<Position> {
+        let mut revealed = Vec::new();
+        let mut queue = VecDeque::from_iter(positions);
+
+        while let Some(position) = queue.pop_front() {
+            let lie = self.grid.liar.then(|| self.lies_high(position));
+            let cell = self.index(position).map(|index| &mut self.cells[index]);
+            let Some(cell) = cell.filter(|cell| !cell.is_revealed() && !cell.is_void()) else {
+                continue;
+            };
+
+            if cell.marking() != Marking::None {
+                continue;
+            }
+
+            cell.reveal();
+            self.cells_revealed += 1;
+            revealed.push(position);
+
+            if cell.is_mine() {
+                info!(?position, "mine revealed");
+                self.mine_revealed = true;
+            } else {
+                self.safe_cells_remaining -= 1;
+            }
+
+            if let Some(high) = lie
+                && !cell.is_mine()
+            {
+                cell.tell_lie(high);
+            } else if let CellType::NonMine { neighbours: 0 } = cell.cell_type() {
+                queue.extend(self.grid.neighbours(position));
+            }
+        }
+
+        revealed
+    }
+
+    /// Which way a liar board's number at `position` is off. The seed
+    /// decides, unless the true number is as low or as high as it goes.
+    fn lies_high(&self, position: Position) -> bool {
+        let Some(CellType::NonMine { neighbours }) = self.get(position).map(Cell::cell_type) else {
+            return false;
+        };
+        let most = self
+            .grid
+            .neighbours(position)
+            .into_iter()
+            .filter(|&n| self.get(n).is_some())
+            .count()
+            * usize::from(self.grid.mines_per_cell);
+
+        match neighbours {
+            0 => true,
+            n if n >= most => false,
+            _ => self
+                .seed
+                .toss(self.index(position).unwrap_or_default() as u64),
+        }
+    }
+
+    /// Moves a hidden cell to its next marking, returning whether it changed.
+    /// Cells that can hold several mines take a flag per mine before the
+    /// marking moves on.
+    pub fn flag(&mut self, position: Position, question_marks: bool) -> bool {
+        let cell = self.index(position).map(|index| &mut self.cells[index]);
+        let Some(cell) = cell.filter(|cell| !cell.is_revealed() && !cell.is_void()) else {
+            return false;
+        };
+
+        let flags = cell.flags();
+
+        if cell.marking() == Marking::Flag && flags < self.grid.mines_per_cell {
+            cell.set_flags(flags + 1);
+        } else {
+            cell.set_marking(cell.marking().next(question_marks));
+        }
+
+        self.flags_placed = self.flags_placed + usize::from(cell.flags()) - usize::from(flags);
+
+        true
+    }
+
+    /// Reveals the neighbours of a number once it has as many flags around
+    /// it as mines. Question marks around it prevent this if they `block`.
+    pub fn chord(&mut self, position: Position, block: bool) -> Vec<Position> {
+        let cell = self.get(position);
+        if let Some(cell) = cell
+            && cell.is_revealed()
+            && let Some(number) = cell.number()
+        {
+            let hidden = || {
+                self.grid
+                    .neighbours(position)
+                    .into_iter()
+                    .filter_map(|n| self.get(n))
+                    .filter(|cell| !cell.is_revealed())
+            };
+
+            let flagged: usize = hidden().map(|cell| usize::from(cell.flags())).sum();
+            let blocked = block && hidden().any(|cell| cell.marking() == Marking::QuestionMark);
+
+            if flagged == number && !blocked {
+                return self.flood(self.grid.neighbours(position));
+            }
+        }
+
+        Vec::new()
+    }
+
+    pub fn can_open_rest(&self) -> bool {
+        self.is_generated
+            && self.status() == GameStatus::InProgress
+            && self.flags_placed == self.mines
+    }
+
+    /// Whether every mine carries exactly its flags, with no flag anywhere
+    /// else.
+    pub fn flags_are_correct(&self) -> bool {
+        self.can_open_rest()
+            && self
+                .cells
+                .iter()
+                .all(|cell| cell.flags() == 0 || cell.flags() == cell.mines())
+    }
+
+    /// Reveals every cell without a flag once there are as many flags as
+    /// mines, clearing question marks on the way.
+    pub fn open_rest(&mut self) -> Vec<Position> {
+        if !self.can_open_rest() {
+            return Vec::new();
+        }
+
+        let unflagged: Vec<_> = self
+            .cells()
+            .filter(|(_, cell)| !cell.is_revealed() && cell.marking() != Marking::Flag)
+            .map(|(position, _)| position)
+            .collect();
+
+        for &position in &unflagged {
+            if let Some(cell) = self.get_mut(position) {
+                cell.set_marking(Marking::None);
+            }
+        }
+
+        self.flood(unflagged)
+    }
+
+    /// Takes the mines out of a revealed mine cell, leaving a safe cell and
+    /// lowering the numbers around it to match. The game carries on with one
+    /// mine fewer. Returns the cells this opens, if the cell turns out empty.
+    pub fn defuse(&mut self, position: Position) -> Vec<Position> {
+        let Some(count) = self
+            .get(position)
+            .filter(|cell| cell.is_mine() && cell.is_revealed())
+            .map(Cell::mines)
+        else {
+            return Vec::new();
+        };
+
+        let neighbours = self.grid.neighbours(position);
+        let around: u8 = neighbours
+            .iter()
+            .filter_map(|&n| self.get(n))
+            .map(Cell::mines)
+            .sum();
+
+        for &n in neighbours.iter() {
+            if let Some(cell) = self.get_mut(n) {
+                cell.remove_neighbouring_mines(count);
+            }
+        }
+
+        if let Some(cell) = self.get_mut(position) {
+            cell.defuse(around);
+        }
+
+        // The defused cell starts lying, and neighbours whose count dropped
+        // to the bottom may have to lie the other way to stay wrong. Lies in
+        // between come from the seed, so they keep their direction.
+        if self.grid.liar {
+            for n in neighbours.iter().copied().chain([position]) {
+                if self
+                    .get(n)
+                    .is_some_and(|cell| cell.is_revealed() && !cell.is_mine())
+                {
+                    let high = self.lies_high(n);
+                    if let Some(cell) = self.get_mut(n) {
+                        cell.tell_lie(high);
+                    }
+                }
+            }
+        }
+
+        self.mines -= usize::from(count);
+        self.mine_revealed = self
+            .cells
+            .iter()
+            .any(|cell| cell.is_mine() && cell.is_revealed());
+        info!(?position, "mine defused");
+
+        if around == 0 && !self.grid.liar {
+            self.flood(neighbours)
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn expose_mines(&mut self) {
+        for cell in &mut self.cells {
+            if cell.is_mine() {
+                cell.reveal();
+            }
+        }
+    }
+
+    /// Flags every mine once all safe cells are open.
+    pub fn flag_remaining_mines(&mut self) {
+        if !self.is_generated || self.safe_cells_remaining > 0 {
+            return;
+        }
+
+        for cell in &mut self.cells {
+            if !cell.is_revealed() && cell.flags() != cell.mines() {
+                self.flags_placed =
+                    self.flags_placed + usize::from(cell.mines()) - usize::from(cell.flags());
+                cell.set_flags(cell.mines());
+            }
+        }
+    }
+
+    /// Flags the hidden neighbours of every number whose hidden neighbours
+    /// can only hold its mines by all being full. Does nothing on liar
+    /// boards, where it would go by the true numbers.
+    pub fn flag_forced_mines(&mut self) {
+        if self.grid.liar {
+            return;
+        }
+
+        let max_flags = self.grid.mines_per_cell;
+
+        let forced: Vec<Position> = self
+            .cells()
+            .filter_map(|(position, cell)| match cell.cell_type() {
+                CellType::NonMine { neighbours } if cell.is_revealed() && neighbours > 0 => {
+                    Some((position, neighbours))
+                }
+                _ => None,
+            })
+            .flat_map(|(position, neighbours)| {
+                let hidden: Neighbours = self
+                    .grid
+                    .neighbours(position)
+                    .into_iter()
+                    .filter(|&n| self.get(n).is_some_and(|cell| !cell.is_revealed()))
+                    .collect();
+
+                if hidden.len() * usize::from(max_flags) == neighbours {
+                    hidden
+                } else {
+                    Neighbours::default()
+                }
+            })
+            .collect();
+
+        for position in forced {
+            if let Some(cell) = self.index(position).map(|index| &mut self.cells[index])
+                && cell.flags() < max_flags
+            {
+                self.flags_placed += usize::from(max_flags - cell.flags());
+                cell.set_flags(max_flags);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal_floods_huge_empty_board() {
+        let grid = Grid::new(300, 300);
+        let mut board = Board::new(grid, 0, Seed::from_day(0));
+
+        let revealed = board.reveal(Position::new(150, 150));
+
+        assert_eq!(revealed.len(), grid.width * grid.height);
+        assert_eq!(board.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn cell_packs_several_mines_and_flags() {
+        let mut cell = Cell::default();
+        for _ in 0..3 {
+            cell.add_mine();
+        }
+        cell.set_flags(2);
+        cell.reveal();
+
+        assert_eq!(cell.mines(), 3);
+        assert_eq!(cell.flags(), 2);
+        assert_eq!(cell.marking(), Marking::Flag);
+        assert!(cell.is_revealed() && !cell.is_void());
+
+        cell.set_marking(Marking::QuestionMark);
+        assert_eq!(cell.flags(), 0);
+        assert_eq!(cell.mines(), 3);
+
+        let mut cell = Cell::default();
+        for _ in 0..8 * Grid::MAX_MINES_PER_CELL {
+            cell.add_neighbouring_mine();
+        }
+        cell.set_flags(Grid::MAX_MINES_PER_CELL);
+
+        assert_eq!(
+            cell.number(),
+            Some(8 * usize::from(Grid::MAX_MINES_PER_CELL))
+        );
+        assert_eq!(cell.flags(), Grid::MAX_MINES_PER_CELL);
+        assert!(!cell.is_mine());
+    }
+
+    #[test]
+    fn first_click_only_moves_the_mines_it_hits() {
+        let grid = Grid::new(16, 16);
+        let seed = Seed::from_day(4);
+        let mines = |board: &Board| -> Vec<Position> {
+            board
+                .cells()
+                .filter(|(_, cell)| cell.is_mine())
+                .map(|(position, _)| position)
+                .collect()
+        };
+
+        let mut dealt = Board::new(
+            Grid {
+                first_click: FirstClick::Anywhere,
+                ..grid
+            },
+            40,
+            seed,
+        );
+        dealt.generate(Position::new(0, 0));
+        let dealt = mines(&dealt);
+
+        // Whichever cell is clicked first, the mines the seed dealt outside
+        // the opening stay where they are.
+        for start in [Position::new(0, 0), Position::new(8, 8)] {
+            let mut board = Board::new(grid, 40, seed);
+            board.reveal(start);
+            let mines = mines(&board);
+            let safe = grid.neighbours(start);
+            let kept: Vec<_> = dealt
+                .iter()
+                .filter(|&&p| p != start && !safe.contains(&p))
+                .collect();
+
+            assert_eq!(mines.len(), 40);
+            assert!(kept.iter().all(|p| mines.contains(p)));
+        }
+    }
+
+    #[test]
+    fn restore_round_trips_a_game() {
+        let grid = Grid::new(9, 9);
+        let mut board = Board::new(grid, 10, Seed::from_day(1));
+        board.reveal(Position::new(4, 4));
+        let hidden = board
+            .cells()
+            .find(|(_, cell)| !cell.is_revealed())
+            .map(|(position, _)| position)
+            .unwrap();
+        board.flag(hidden, false);
+
+        let restored = Board::restore(grid, 10, board.seed(), board.packed(), true).unwrap();
+
+        assert_eq!(restored.packed(), board.packed());
+        assert_eq!(restored.flags_placed(), 1);
+        assert_eq!(restored.cells_revealed(), board.cells_revealed());
+        assert_eq!(
+            restored.safe_cells_remaining(),
+            board.safe_cells_remaining()
+        );
+        assert_eq!(restored.status(), GameStatus::InProgress);
+        assert!(Board::restore(grid, 10, board.seed(), vec![0; 80], true).is_none());
+    }
+
+    #[test]
+    fn restore_leaves_masked_cells_out() {
+        let grid = Grid::new(4, 4);
+        let mask = Mask::from_rows(&[".##.", "####", "####", ".##."]);
+        let board = Board::masked(grid, 3, Seed::from_day(3), &mask);
+
+        let restored = Board::restore(grid, 3, board.seed(), board.packed(), false).unwrap();
+
+        assert_eq!(restored.safe_cells_remaining(), 12 - 3);
+        assert_eq!(restored.cells().count(), 12);
+    }
+
+    #[test]
+    fn defused_liar_boards_keep_lying() {
+        let grid = Grid {
+            liar: true,
+            ..Grid::new(5, 5)
+        };
+        let mine = Position::new(2, 2);
+
+        for day in 0..32 {
+            let mines = [mine, Position::new(0, 4)];
+            let mut board = Board::with_mines(grid, Seed::from_day(day), &mines);
+
+            let safe: Vec<_> = board
+                .cells()
+                .filter(|(_, cell)| !cell.is_mine())
+                .map(|(position, _)| position)
+                .collect();
+            for &position in &safe {
+                board.reveal(position);
+            }
+
+            board.reveal(mine);
+            board.defuse(mine);
+
+            for (_, cell) in board.cells().filter(|(_, cell)| cell.is_revealed()) {
+                let CellType::NonMine { neighbours } = cell.cell_type() else {
+                    continue;
+                };
+
+                assert_eq!(cell.number().unwrap().abs_diff(neighbours), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn liar_numbers_are_off_by_one() {
+        let grid = Grid {
+            liar: true,
+            ..Grid::new(6, 6)
+        };
+        let mines = [(0, 0), (1, 3), (3, 1), (4, 4), (5, 0)]
+            .map(|(row, column)| Position::new(row, column));
+        let mut board = Board::with_mines(grid, Seed::from_day(2), &mines);
+
+        let safe: Vec<_> = board
+            .cells()
+            .filter(|(_, cell)| !cell.is_mine())
+            .map(|(position, _)| position)
+            .collect();
+        for &position in &safe {
+            // Liar boards open only the cell clicked.
+            assert_eq!(board.reveal(position), vec![position]);
+        }
+
+        for position in safe {
+            let cell = board.get(position).unwrap();
+            let CellType::NonMine { neighbours } = cell.cell_type() else {
+                unreachable!();
+            };
+
+            assert_eq!(cell.number().unwrap().abs_diff(neighbours), 1);
+        }
+        assert_eq!(board.status(), GameStatus::Won);
+    }
+}
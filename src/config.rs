@@ -0,0 +1,26 @@
+use tracing::warn;
+
+use crate::{settings::Settings, storage};
+
+/// Settings are kept as TOML so that players can edit them by hand.
+const FILE: &str = "config.toml";
+
+/// Reads the settings. Missing or unreadable settings fall back to the
+/// defaults.
+pub fn load() -> Settings {
+    let Some(contents) = storage::read(FILE) else {
+        return Settings::default();
+    };
+
+    toml::from_str(&contents).unwrap_or_else(|error| {
+        warn!(file = FILE, %error, "could not parse file");
+        Settings::default()
+    })
+}
+
+pub fn save(settings: &Settings) {
+    match toml::to_string_pretty(settings) {
+        Ok(contents) => storage::write(FILE, &contents),
+        Err(error) => warn!(file = FILE, %error, "could not save file"),
+    }
+}
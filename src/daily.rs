@@ -0,0 +1,111 @@
+use std::fmt;
+
+use minesweeper::seed::Seed;
+use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar day in UTC, counted from the Unix epoch, so that everyone
+/// plays the same daily board regardless of where they are.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub struct Day(u64);
+
+impl Day {
+    pub fn today() -> Self {
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Self(elapsed.as_secs() / 86_400)
+    }
+
+    pub fn seed(self) -> Seed {
+        Seed::from_day(self.0)
+    }
+
+    pub fn follows(self, other: Day) -> bool {
+        self.0 == other.0 + 1
+    }
+}
+
+/// Formats the day as an ISO 8601 date.
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Howard Hinnant's days_from_civil, run backwards.
+        let z = self.0 as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use minesweeper::{
+        board::{Board, Position},
+        difficulty::Difficulty,
+        grid::{FirstClick, Grid},
+    };
+
+    use super::*;
+
+    fn mines(board: &Board) -> Vec<Position> {
+        board
+            .cells()
+            .filter(|(_, cell)| cell.is_mine())
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    #[test]
+    fn everyone_gets_the_same_daily_board() {
+        let day = Day(20_000);
+        let (width, height, count) = Difficulty::Medium.board();
+        let grid = Grid::new(width, height);
+
+        let mut dealt = Board::new(
+            Grid {
+                first_click: FirstClick::Anywhere,
+                ..grid
+            },
+            count,
+            day.seed(),
+        );
+        dealt.reveal(Position::new(0, 0));
+        let dealt = mines(&dealt);
+
+        // Players opening in different places only see the mines under
+        // their openings moved.
+        for start in [
+            Position::new(0, 0),
+            Position::new(7, 9),
+            Position::new(13, 17),
+        ] {
+            let mut board = Board::new(grid, count, day.seed());
+            board.reveal(start);
+            let mines = mines(&board);
+            let opening = grid.neighbours(start);
+
+            assert_eq!(mines.len(), count);
+            assert!(
+                dealt
+                    .iter()
+                    .filter(|&&p| p != start && !opening.contains(&p))
+                    .all(|p| mines.contains(p))
+            );
+        }
+
+        assert_ne!(day.seed(), Day(20_001).seed());
+    }
+}
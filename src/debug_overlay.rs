@@ -0,0 +1,89 @@
+use std::{cell::RefCell, collections::VecDeque, time::Duration};
+
+use iced::{
+    Color, Point, Renderer, Size,
+    widget::canvas::{self, Frame, Text},
+};
+use web_time::Instant;
+
+/// Hidden overlay with rendering statistics, toggled from the board canvas.
+#[derive(Debug, Default)]
+pub struct DebugOverlay {
+    visible: bool,
+    frames: RefCell<VecDeque<Instant>>,
+}
+
+#[derive(Debug)]
+pub struct FrameStats {
+    pub draw_time: Duration,
+    pub cells_drawn: usize,
+    /// Whether the cached cells were drawn again this frame, because the
+    /// board, the viewport or the cascade wave changed.
+    pub cache_rebuilt: bool,
+    pub flags_placed: usize,
+    pub cells_revealed: usize,
+    pub safe_cells_remaining: usize,
+    pub interaction: String,
+}
+
+impl DebugOverlay {
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.frames.borrow_mut().clear();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn draw(
+        &self,
+        renderer: &Renderer,
+        size: Size,
+        stats: FrameStats,
+    ) -> canvas::Geometry<Renderer> {
+        let now = Instant::now();
+        let mut frames = self.frames.borrow_mut();
+        frames.push_back(now);
+        while frames
+            .front()
+            .is_some_and(|&frame| now - frame > Duration::from_secs(1))
+        {
+            frames.pop_front();
+        }
+
+        let lines = [
+            format!("fps: {}", frames.len()),
+            format!("draw: {:.2} ms", stats.draw_time.as_secs_f64() * 1000.0),
+            format!("cells: {}", stats.cells_drawn),
+            format!(
+                "cache: {}",
+                if stats.cache_rebuilt { "miss" } else { "hit" }
+            ),
+            format!(
+                "flags: {} revealed: {} safe left: {}",
+                stats.flags_placed, stats.cells_revealed, stats.safe_cells_remaining
+            ),
+            format!("input: {}", stats.interaction),
+        ];
+
+        let mut frame = Frame::new(renderer, size);
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            Size::new(size.width.min(300.0), 8.0 + 16.0 * lines.len() as f32),
+            Color::from_rgba8(0, 0, 0, 0.7),
+        );
+
+        for (i, content) in lines.into_iter().enumerate() {
+            frame.fill_text(Text {
+                content,
+                position: Point::new(4.0, 4.0 + 16.0 * i as f32),
+                size: 13.0.into(),
+                color: Color::from_rgb8(0x40, 0xff, 0x40),
+                ..Default::default()
+            });
+        }
+
+        frame.into_geometry()
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub enum Difficulty {
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+    Huge,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Huge,
+    ];
+
+    /// The width, height and number of mines of the board.
+    pub fn board(self) -> (usize, usize, usize) {
+        match self {
+            Difficulty::Easy => (10, 8, 10),
+            Difficulty::Medium => (18, 14, 40),
+            Difficulty::Hard => (24, 20, 99),
+            Difficulty::Huge => (200, 200, 8000),
+        }
+    }
+}
@@ -1,393 +1,1982 @@
-use std::collections::HashMap;
+use std::{
+    cell,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::RangeInclusive,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use iced::{
+    Alignment::Center,
     Color, Element,
     Length::Fill,
-    Point, Renderer, Size, Theme,
+    Point, Renderer, Size, Subscription, Theme, Vector,
     advanced::{graphics::core::event, mouse},
+    keyboard::{self, key::Named},
     mouse::Button,
+    time, touch,
     widget::{
-        Canvas,
-        canvas::{self, Event, Frame, Text},
+        Canvas, button,
+        canvas::{self, Event, Frame, Path, Stroke, Text, path},
+        column, container, horizontal_space, mouse_area, progress_bar, row, text,
     },
+    window,
 };
 use itertools::iproduct;
-use rand::seq::IteratorRandom;
+use minesweeper::{
+    board::{Board, Cell, CellType, GameStatus, Marking, Position},
+    grid::{FirstClick, Grid, Shape},
+    mask::Mask,
+    seed::Seed,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use web_time::Instant;
 
-#[derive(Clone, Copy, Debug)]
-pub enum CellType {
-    Mine,
-    NonMine { neighbours: usize },
-}
+#[cfg(feature = "sound")]
+use crate::sound::{self, Sound};
+use crate::{
+    analysis::Analysis,
+    animation::{Burst, Cascade},
+    debug_overlay::{DebugOverlay, FrameStats},
+    metrics::{self, Clicks, Metrics},
+    patterns,
+    puzzle::Layout,
+    replay::Replay,
+    settings::{Action, Settings},
+    smiley::Face,
+    solver, sprites,
+    theme::{BoardTheme, TileStyle},
+    timer::Timer,
+    topology::{self, Topology},
+};
 
-#[derive(Clone, Copy, Debug)]
-pub struct Cell {
-    pub is_revealed: bool,
-    pub marking: Marking,
-    pub cell_type: CellType,
+/// The player's zoom and pan on top of the board fitted to the canvas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Viewport {
+    zoom: f32,
+    pan: Vector,
 }
 
-impl Default for Cell {
+impl Default for Viewport {
     fn default() -> Self {
         Self {
-            is_revealed: false,
-            marking: Marking::None,
-            cell_type: CellType::NonMine { neighbours: 0 },
+            zoom: 1.0,
+            pan: Vector::ZERO,
         }
     }
 }
 
-impl Cell {
-    pub fn mine() -> Self {
-        Cell {
-            cell_type: CellType::Mine,
-            ..Default::default()
+/// Where the board sits within the canvas.
+#[derive(Clone, Copy)]
+struct BoardLayout {
+    origin: Vector,
+    cell_size: f32,
+    topology: &'static dyn Topology,
+}
+
+impl BoardLayout {
+    /// The cell under a point relative to the canvas.
+    fn position(&self, point: Point) -> Position {
+        let point = point - self.origin;
+
+        self.topology.position_at(Point::new(
+            point.x / self.cell_size,
+            point.y / self.cell_size,
+        ))
+    }
+
+    /// Makes one unit in the frame cover one cell.
+    fn apply(&self, frame: &mut Frame) {
+        frame.translate(self.origin);
+        frame.scale(self.cell_size);
+    }
+
+    /// The part of the board that shows within a canvas of the given size.
+    /// It reaches a cell past the edges, since cells that are not squares
+    /// stick out of their row and column.
+    fn visible_area(&self, bounds: Size, grid: Grid) -> VisibleArea {
+        let first = self.position(Point::ORIGIN);
+        let last = self.position(Point::new(bounds.width, bounds.height));
+
+        VisibleArea {
+            rows: (first.row() - 1).max(0)..=(last.row() + 1).min(grid.height as i32 - 1),
+            columns: (first.column() - 1).max(0)..=(last.column() + 1).min(grid.width as i32 - 1),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum Marking {
-    None,
+/// The rows and columns of the board that are drawn, so that the time spent
+/// drawing depends on the canvas rather than the size of the board.
+struct VisibleArea {
+    rows: RangeInclusive<i32>,
+    columns: RangeInclusive<i32>,
+}
+
+impl VisibleArea {
+    fn contains(&self, position: Position) -> bool {
+        self.rows.contains(&position.row()) && self.columns.contains(&position.column())
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Position> {
+        iproduct!(self.rows.clone(), self.columns.clone())
+            .map(|(row, column)| Position::new(row as usize, column as usize))
+    }
+}
+
+/// Identifies a state of a game, so that the canvas can tell when it has to
+/// redraw the cells. Unique across games, since the canvas outlives them.
+fn next_revision() -> u64 {
+    static REVISION: AtomicU64 = AtomicU64::new(0);
+
+    REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+const MAX_ZOOM: f32 = 8.0;
+const ZOOM_STEP: f32 = 1.25;
+/// How far the cursor moves with a button held before the press becomes a pan.
+const PAN_THRESHOLD: f32 = 4.0;
+const HEADER_HEIGHT: f32 = 40.0;
+const FACE_SIZE: f32 = 28.0;
+const HIGHLIGHT_DURATION: Duration = Duration::from_secs(1);
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+const HINT_PENALTY: Duration = Duration::from_secs(10);
+/// How many moves undo reaches back; older boards are dropped.
+const UNDO_LIMIT: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct GameState {
+    board: Board,
+    revision: u64,
+    settings: Settings,
+    recently_revealed: Vec<Position>,
+    revealed_at: Option<Instant>,
+    cascade: Option<Cascade>,
+    paused_at: Option<Instant>,
+    pressing: bool,
+    /// Physical pixels per logical pixel on the window's monitor.
+    scale_factor: f32,
+    /// Whether the face and the Restart button start a new game.
+    restartable: bool,
+    /// The explosion or confetti that marks the end of the game.
+    burst: Option<Burst>,
+    moves: usize,
+    timer: Timer,
+    replay: Replay,
+    /// Boards that undo and redo restore.
+    undo_stack: VecDeque<Board>,
+    redo_stack: Vec<Board>,
+    undos_used: usize,
+    hints_used: usize,
+    /// Mines forgiven by the insurance assist, marked on the board.
+    defused: Vec<Position>,
+    three_bv: usize,
+    clicks: Clicks,
+    hint: Option<Position>,
+    no_hint_found: bool,
+    probabilities: Option<HashMap<Position, f32>>,
+    analysis: Option<Analysis>,
+    show_analysis: bool,
+}
+
+/// An unfinished game in a form that can be written to disk. Cells are
+/// stored row by row in their packed form.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedGame {
+    #[serde(flatten)]
+    grid: Grid,
+    mines: usize,
+    seed: Seed,
+    settings: Settings,
+    cells: Vec<u16>,
+    has_revealed_any: bool,
+    moves: usize,
+    undos_used: usize,
+    #[serde(default)]
+    hints_used: usize,
+    #[serde(default)]
+    defused: Vec<Position>,
+    three_bv: usize,
+    clicks: Clicks,
+    elapsed: Duration,
+    replay: Replay,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum Message {
+    Reveal(Position),
+    ToggleMark(Position),
+    UnlockMark(Position),
+    RevealSurrounding(Position),
+    OpenRest,
+    Undo,
+    Redo,
+    Hint,
+    ToggleProbabilities,
+    ToggleAnalysis,
+    /// Asks for a new game. The application handles it, so that it can
+    /// confirm abandoning this one.
+    Restart,
+    #[serde(skip)]
+    Tick(Instant),
+    /// Stops the clock and hides the board while the window is in the
+    /// background.
+    #[serde(skip)]
+    Pause,
+    #[serde(skip)]
+    Resume,
+    /// Whether a button is held down on the board, for the face above it.
+    #[serde(skip)]
+    Pressing(bool),
+}
+
+impl GameState {
+    /// A new game on `grid`, cut to the shape of `mask` if there is one.
+    pub fn new(
+        grid: Grid,
+        mines: usize,
+        seed: Seed,
+        mask: Option<Mask>,
+        settings: Settings,
+    ) -> Self {
+        Self {
+            board: match &mask {
+                Some(mask) => Board::masked(grid, mines, seed, mask),
+                None => Board::new(grid, mines, seed),
+            },
+            revision: next_revision(),
+            replay: Replay::new(grid, mines, seed, mask, settings.clone()),
+            settings,
+            recently_revealed: Vec::new(),
+            revealed_at: None,
+            cascade: None,
+            paused_at: None,
+            pressing: false,
+            scale_factor: 1.0,
+            restartable: true,
+            burst: None,
+            moves: 0,
+            timer: Timer::default(),
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            undos_used: 0,
+            hints_used: 0,
+            defused: Vec::new(),
+            three_bv: 0,
+            clicks: Clicks::default(),
+            hint: None,
+            no_hint_found: false,
+            probabilities: None,
+            analysis: None,
+            show_analysis: false,
+        }
+    }
+
+    pub fn save(&self) -> SavedGame {
+        SavedGame {
+            grid: self.board.grid(),
+            mines: self.board.mines(),
+            seed: self.board.seed(),
+            settings: self.settings.clone(),
+            cells: self.board.packed(),
+            has_revealed_any: self.board.is_generated(),
+            moves: self.moves,
+            undos_used: self.undos_used,
+            hints_used: self.hints_used,
+            defused: self.defused.clone(),
+            three_bv: self.three_bv,
+            clicks: self.clicks,
+            elapsed: self.elapsed(),
+            replay: self.replay.clone(),
+        }
+    }
+
+    /// Continues a saved game, or returns `None` if its cells do not match
+    /// its dimensions.
+    pub fn resume(saved: SavedGame) -> Option<Self> {
+        let board = Board::restore(
+            saved.grid,
+            saved.mines,
+            saved.seed,
+            saved.cells,
+            saved.has_revealed_any,
+        )?;
+
+        let now = Instant::now();
+        // The mask comes back with the cells and the replay.
+        let mut game = Self::new(saved.grid, saved.mines, saved.seed, None, saved.settings);
+
+        game.board = board;
+        game.revision = next_revision();
+        game.moves = saved.moves;
+        game.undos_used = saved.undos_used;
+        game.hints_used = saved.hints_used;
+        game.defused = saved.defused;
+        game.three_bv = saved.three_bv;
+        game.clicks = saved.clicks;
+        game.timer = Timer::with_elapsed(saved.elapsed);
+        game.replay = saved.replay;
+        game.replay.resume(now);
+
+        if game.board.is_generated() {
+            game.timer.start(now);
+        }
+
+        Some(game)
+    }
+
+    /// The preferred size of the game view, with cells at their configured
+    /// size.
+    pub fn size(&self) -> Size {
+        let board = self.board_size();
+        let cell_size = self.cell_size();
+
+        Size::new(
+            board.width * cell_size,
+            board.height * cell_size + HEADER_HEIGHT,
+        )
+    }
+
+    /// The side of a cell in logical pixels before the board is scaled to
+    /// fit, rounded to cover whole physical pixels.
+    pub fn cell_size(&self) -> f32 {
+        (self.settings.cell_size.pixels() * self.scale_factor).round() / self.scale_factor
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Lines cells up with the physical pixels of a monitor with the given
+    /// scale factor.
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Disables the face and the Restart button for boards that cannot be
+    /// played again.
+    pub fn with_restart(mut self, restartable: bool) -> Self {
+        self.restartable = restartable;
+        self
+    }
+
+    /// Swaps the dealt board for one with the mines of `layout`, with its
+    /// starting cell already open and the clock running.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.board = Board::with_mines(self.board.grid(), self.board.seed(), &layout.mines);
+        self.board.reveal(layout.start);
+        self.three_bv = metrics::three_bv(&self.board);
+        self.timer.start(Instant::now());
+        self.replay.set_layout(layout);
+        self
+    }
+
+    /// Takes over settings changed in the middle of the game. No flags keeps
+    /// the value the game started with, as it decides which records the game
+    /// counts towards.
+    pub fn set_settings(&mut self, mut settings: Settings) {
+        settings.input.no_flags = self.settings.input.no_flags;
+        self.settings = settings;
+        self.revision = next_revision();
+    }
+
+    /// Rounds a length down to whole physical pixels, keeping at least one.
+    fn snap(&self, length: f32) -> f32 {
+        (length * self.scale_factor).floor().max(1.0) / self.scale_factor
+    }
+
+    fn topology(&self) -> &'static dyn Topology {
+        topology::of(self.board.grid().shape)
+    }
+
+    /// The extent of the board in board units.
+    fn board_size(&self) -> Size {
+        let grid = self.board.grid();
+        self.topology().size(grid.width, grid.height)
+    }
+
+    /// Scales the board to fit the canvas, then applies the zoom and pan.
+    /// The pan is limited so the board never leaves the canvas.
+    fn layout(&self, bounds: Size, viewport: Viewport) -> BoardLayout {
+        let board = self.board_size();
+        // Cells cover whole physical pixels, so that the tiles drawn and the
+        // cells hit by the cursor share their edges.
+        let cell_size = self
+            .snap((bounds.width / board.width).min(bounds.height / board.height) * viewport.zoom);
+        let overflow = Vector::new(
+            (board.width * cell_size - bounds.width).max(0.0) / 2.0,
+            (board.height * cell_size - bounds.height).max(0.0) / 2.0,
+        );
+
+        let origin = Vector::new(
+            (bounds.width - board.width * cell_size) / 2.0
+                + viewport.pan.x.clamp(-overflow.x, overflow.x),
+            (bounds.height - board.height * cell_size) / 2.0
+                + viewport.pan.y.clamp(-overflow.y, overflow.y),
+        );
+
+        BoardLayout {
+            origin: Vector::new(
+                (origin.x * self.scale_factor).round() / self.scale_factor,
+                (origin.y * self.scale_factor).round() / self.scale_factor,
+            ),
+            cell_size,
+            topology: self.topology(),
+        }
+    }
+
+    /// Zooms by `factor` while keeping the cell under `point` in place.
+    fn zoom(&self, viewport: &mut Viewport, bounds: Size, point: Point, factor: f32) {
+        let point = Vector::new(point.x, point.y);
+        let before = self.layout(bounds, *viewport);
+        let anchor = (point - before.origin) * (1.0 / before.cell_size);
+
+        viewport.zoom = (viewport.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let centred = self.layout(
+            bounds,
+            Viewport {
+                pan: Vector::ZERO,
+                ..*viewport
+            },
+        );
+        viewport.pan = point - anchor * centred.cell_size - centred.origin;
+
+        self.pan(viewport, bounds, Vector::ZERO);
+    }
+
+    /// Moves the board by `delta`, keeping only the part of the pan that
+    /// takes effect so panning back from an edge responds immediately.
+    fn pan(&self, viewport: &mut Viewport, bounds: Size, delta: Vector) {
+        viewport.pan = viewport.pan + delta;
+
+        let centred = self.layout(
+            bounds,
+            Viewport {
+                pan: Vector::ZERO,
+                ..*viewport
+            },
+        );
+        viewport.pan = self.layout(bounds, *viewport).origin - centred.origin;
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.settings.assists.undo && !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.settings.assists.undo && !self.redo_stack.is_empty()
+    }
+
+    /// Whether the game is played without flags.
+    pub fn is_no_flags(&self) -> bool {
+        self.settings.input.no_flags
+    }
+
+    pub fn undos_used(&self) -> usize {
+        self.undos_used
+    }
+
+    /// How many hints pointed out a cell this game.
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+
+    /// Whether a mine hit was forgiven this game.
+    pub fn was_insured(&self) -> bool {
+        !self.defused.is_empty()
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            three_bv: self.three_bv,
+            solved_three_bv: metrics::solved_three_bv(&self.board),
+            clicks: self.clicks,
+            elapsed: self.elapsed(),
+        }
+    }
+
+    /// The number of mines the game was dealt. The board's own count drops
+    /// as the insurance assist defuses mines.
+    pub fn mines(&self) -> usize {
+        self.replay.mines()
+    }
+
+    pub fn mask(&self) -> Option<&Mask> {
+        self.replay.mask()
+    }
+
+    /// The fixed board, if this is a puzzle.
+    pub fn fixed_layout(&self) -> Option<&Layout> {
+        self.replay.layout()
+    }
+
+    /// Whether best times are kept for this game's board: a classic grid
+    /// without a mask.
+    pub fn is_classic(&self) -> bool {
+        self.board.grid().is_classic() && self.mask().is_none()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn has_started(&self) -> bool {
+        self.board.is_generated()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.board.status()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.board.grid()
+    }
+
+    pub fn seed(&self) -> Seed {
+        self.board.seed()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    /// Whether the explosion or confetti at the end of the game is still
+    /// playing.
+    pub fn is_animating(&self) -> bool {
+        self.burst.is_some()
+    }
+
+    pub fn mines_remaining(&self) -> isize {
+        self.board.mines_remaining()
+    }
+
+    /// Safe cells still to open, counting those the cascade has yet to
+    /// reach as closed.
+    fn safe_cells_hidden(&self) -> usize {
+        let waiting = self
+            .cascade
+            .as_ref()
+            .map_or(0, |cascade| cascade.hidden(Instant::now()));
+
+        self.board.safe_cells_remaining() + waiting
+    }
+
+    /// The share of safe cells that are open.
+    fn progress(&self) -> f32 {
+        let safe_cells = self.board.cells_revealed() + self.board.safe_cells_remaining();
+
+        if safe_cells == 0 {
+            1.0
+        } else {
+            1.0 - self.safe_cells_hidden() as f32 / safe_cells as f32
+        }
+    }
+
+    /// Places or cycles a marking, unless a locked flag is in the way.
+    fn toggle_mark(&mut self, position: Position, unlock: bool) -> bool {
+        let locked = self.board.get(position).is_some_and(|cell| {
+            cell.flags() == self.board.grid().mines_per_cell
+                && self.settings.input.flag_lock
+                && !unlock
+        });
+
+        !locked
+            && self
+                .board
+                .flag(position, self.settings.marks.question_marks)
+    }
+
+    /// Restarts the clock after a pause, leaving the paused time out of the
+    /// replay as well.
+    fn unpause(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            let now = Instant::now();
+
+            info!("resumed");
+            self.replay.skip(now - paused_at);
+            self.timer.start(now);
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.status() != GameStatus::InProgress
+    }
+
+    fn restore(&mut self, board: Board) -> Board {
+        let current = std::mem::replace(&mut self.board, board);
+
+        self.recently_revealed.clear();
+        self.revealed_at = None;
+        self.cascade = None;
+        self.burst = None;
+        self.hint = None;
+        self.analysis = None;
+        self.show_analysis = false;
+        self.refresh_probabilities();
+
+        let now = Instant::now();
+        if self.is_over() {
+            self.timer.stop(now);
+        } else {
+            self.timer.start(now);
+        }
+
+        current
+    }
+
+    fn undo(&mut self) {
+        if self.can_undo()
+            && let Some(snapshot) = self.undo_stack.pop_back()
+        {
+            let current = self.restore(snapshot);
+            self.redo_stack.push(current);
+            self.undos_used += 1;
+            info!(undos_used = self.undos_used, "move undone");
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.can_redo()
+            && let Some(snapshot) = self.redo_stack.pop()
+        {
+            let current = self.restore(snapshot);
+            self.undo_stack.push_back(current);
+            info!("move redone");
+        }
+    }
+
+    fn hint(&mut self) {
+        if self.is_over() {
+            return;
+        }
+
+        let grid = self.board.grid();
+        self.hint = if self.board.is_generated() {
+            solver::find_safe_cell(&self.board)
+        } else if grid.first_click != FirstClick::Anywhere {
+            Some(self.board.central_cell())
+        } else {
+            None
+        };
+        self.no_hint_found = self.hint.is_none();
+        self.hints_used += usize::from(self.hint.is_some());
+
+        if self.hint.is_some() && self.settings.assists.hint_penalty {
+            self.timer.add_penalty(HINT_PENALTY);
+        }
+
+        info!(hint = ?self.hint, "hint requested");
+    }
+
+    fn refresh_probabilities(&mut self) {
+        if let Some(probabilities) = &mut self.probabilities {
+            *probabilities = solver::mine_probabilities(&self.board);
+        }
+    }
+
+    fn toggle_probabilities(&mut self) {
+        self.probabilities = match self.probabilities {
+            Some(_) => None,
+            None => Some(solver::mine_probabilities(&self.board)),
+        };
+    }
+
+    pub fn update(&mut self, message: Message) {
+        if self.settings.input.no_flags
+            && let Message::ToggleMark(_) | Message::UnlockMark(_) | Message::RevealSurrounding(_) =
+                message
+        {
+            return;
+        }
+
+        let previously_revealed = self.recently_revealed.len();
+        let was_in_progress = !self.is_over();
+        let mut marked = false;
+        // The board as it was before a move, kept for undo and for the
+        // analysis of a losing move.
+        let before = (!self.is_over()
+            && matches!(
+                message,
+                Message::Reveal(_)
+                    | Message::ToggleMark(_)
+                    | Message::UnlockMark(_)
+                    | Message::RevealSurrounding(_)
+                    | Message::OpenRest
+            ))
+        .then(|| self.board.clone());
+
+        match message {
+            Message::Tick(_) => {}
+            Message::Pause => {
+                if self.timer.is_running() && !self.is_over() {
+                    let now = Instant::now();
+
+                    info!("paused");
+                    self.timer.stop(now);
+                    self.paused_at = Some(now);
+                }
+                return;
+            }
+            Message::Resume => {
+                self.unpause();
+                return;
+            }
+            Message::Pressing(pressing) => {
+                self.pressing = pressing;
+                return;
+            }
+            _ => {
+                self.pressing = false;
+                // Anything the player does picks the game up again.
+                self.unpause();
+                self.replay.record(message, Instant::now());
+                self.revision = next_revision();
+            }
+        }
+
+        if !self.is_over() {
+            match message {
+                Message::Reveal(_) => self.clicks.left += 1,
+                Message::ToggleMark(_) | Message::UnlockMark(_) => self.clicks.right += 1,
+                Message::RevealSurrounding(_) => self.clicks.chord += 1,
+                _ => {}
+            }
+        }
+
+        match message {
+            Message::Reveal(position) => {
+                let is_first = !self.board.is_generated();
+                let revealed = self.board.reveal(position);
+                self.recently_revealed.extend(revealed);
+
+                if is_first {
+                    self.three_bv = metrics::three_bv(&self.board);
+                    self.timer.start(Instant::now());
+                }
+            }
+            Message::ToggleMark(position) => marked = self.toggle_mark(position, false),
+            Message::UnlockMark(position) => marked = self.toggle_mark(position, true),
+            Message::RevealSurrounding(position) => {
+                let revealed = self
+                    .board
+                    .chord(position, self.settings.marks.question_marks_block_chords);
+                self.recently_revealed.extend(revealed);
+            }
+            Message::OpenRest => {
+                let revealed = self.board.open_rest();
+                self.recently_revealed.extend(revealed);
+            }
+            Message::Undo => {
+                self.undo();
+                return;
+            }
+            Message::Redo => {
+                self.redo();
+                return;
+            }
+            Message::Hint => self.hint(),
+            Message::ToggleProbabilities => self.toggle_probabilities(),
+            Message::ToggleAnalysis => self.show_analysis = !self.show_analysis,
+            Message::Restart | Message::Pause | Message::Resume | Message::Pressing(_) => {
+                unreachable!("handled above")
+            }
+            Message::Tick(now) => {
+                self.timer.tick(now);
+
+                if self
+                    .revealed_at
+                    .is_some_and(|at| now - at >= HIGHLIGHT_DURATION)
+                {
+                    self.recently_revealed.clear();
+                    self.revealed_at = None;
+                }
+
+                if self
+                    .cascade
+                    .as_ref()
+                    .is_some_and(|cascade| cascade.is_finished(now))
+                {
+                    self.cascade = None;
+                }
+
+                if self
+                    .burst
+                    .as_ref()
+                    .is_some_and(|burst| burst.is_finished(now))
+                {
+                    self.burst = None;
+                }
+            }
+        }
+
+        if self.settings.assists.insurance
+            && was_in_progress
+            && !self.was_insured()
+            && self.status() == GameStatus::Lost
+        {
+            let mines: Vec<_> = self.recently_revealed[previously_revealed..]
+                .iter()
+                .copied()
+                .filter(|&position| self.board.get(position).is_some_and(|cell| cell.is_mine()))
+                .collect();
+
+            for position in mines {
+                let opened = self.board.defuse(position);
+                self.recently_revealed.extend(opened);
+                self.defused.push(position);
+            }
+
+            self.three_bv = metrics::three_bv(&self.board);
+        }
+
+        // Flags can only be correct once the last one is placed, so this is
+        // checked after marks alone.
+        if marked && self.settings.assists.auto_open && self.board.flags_are_correct() {
+            let revealed = self.board.open_rest();
+            self.recently_revealed.extend(revealed);
+        }
+
+        let revealed = self.recently_revealed.len() > previously_revealed;
+
+        if revealed {
+            let now = Instant::now();
+
+            self.recently_revealed.drain(..previously_revealed);
+            self.revealed_at = Some(now);
+            self.cascade = Cascade::new(self.board.grid(), &self.recently_revealed, now);
+            self.refresh_probabilities();
+        }
+
+        if revealed || marked {
+            self.moves += 1;
+            debug!(?message, moves = self.moves, "move");
+
+            self.hint = None;
+            self.no_hint_found = false;
+        }
+
+        if was_in_progress && self.is_over() {
+            self.timer.stop(Instant::now());
+            info!(status = ?self.status(), elapsed = ?self.elapsed(), moves = self.moves, "game over");
+
+            if self.status() == GameStatus::Lost {
+                if let Some(board_before) = &before {
+                    let fatal = self
+                        .recently_revealed
+                        .iter()
+                        .find(|&&position| self.board.get(position).is_some_and(|c| c.is_mine()))
+                        .copied();
+
+                    self.analysis = Some(Analysis::new(
+                        board_before,
+                        &self.board,
+                        fatal,
+                        !matches!(message, Message::Reveal(_)),
+                    ));
+                }
+
+                self.board.expose_mines();
+            }
+
+            let theme = self.settings.appearance.theme();
+            let fatal = self.analysis.as_ref().and_then(|analysis| analysis.fatal);
+
+            self.burst = match self.status() {
+                GameStatus::Won => {
+                    let mut colors = theme.numbers.0.to_vec();
+                    colors.extend([theme.flag, theme.highlight]);
+                    Some(Burst::confetti(&colors, Instant::now()))
+                }
+                _ => fatal.map(|fatal| {
+                    Burst::explosion(
+                        self.topology().centre(fatal),
+                        &[theme.fatal, theme.flag, theme.highlight, theme.glyph],
+                        Instant::now(),
+                    )
+                }),
+            };
+        }
+
+        if (revealed || marked)
+            && self.settings.assists.undo
+            && let Some(before) = before
+        {
+            if self.undo_stack.len() == UNDO_LIMIT {
+                self.undo_stack.pop_front();
+            }
+
+            self.undo_stack.push_back(before);
+            self.redo_stack.clear();
+        }
+
+        if revealed
+            && self.settings.assists.auto_flag
+            && !self.settings.input.no_flags
+            && !self.is_over()
+        {
+            self.board.flag_forced_mines();
+        }
+
+        if self.settings.assists.auto_finish
+            && !self.settings.input.no_flags
+            && was_in_progress
+            && self.status() == GameStatus::Won
+        {
+            self.board.flag_remaining_mines();
+        }
+
+        #[cfg(feature = "sound")]
+        {
+            let sound = match self.status() {
+                GameStatus::Won if was_in_progress => Some(Sound::Win),
+                GameStatus::Lost if was_in_progress => Some(Sound::Explosion),
+                _ if revealed && matches!(message, Message::RevealSurrounding(_)) => {
+                    Some(Sound::Chord)
+                }
+                _ if revealed => Some(Sound::Reveal),
+                _ if marked => Some(Sound::Flag),
+                _ => None,
+            };
+
+            if let Some(sound) = sound {
+                sound::play(sound, &self.settings.sound);
+            }
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let timer = if self.timer.is_running() {
+            // The web timer reports its own kind of instant, so the clock is
+            // read again instead.
+            time::every(Duration::from_secs(1)).map(|_| Message::Tick(Instant::now()))
+        } else {
+            Subscription::none()
+        };
+
+        let highlight =
+            if self.revealed_at.is_some() || self.cascade.is_some() || self.burst.is_some() {
+                window::frames().map(Message::Tick)
+            } else {
+                Subscription::none()
+            };
+
+        // Focus changes are followed from the first move on, while there is
+        // a clock to stop.
+        let focus = if self.has_started() && !self.is_over() {
+            iced::event::listen_with(|event, _, _| match event {
+                iced::Event::Window(window::Event::Unfocused) => Some(Message::Pause),
+                iced::Event::Window(window::Event::Focused) => Some(Message::Resume),
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([timer, highlight, focus])
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let board = match &self.analysis {
+            _ if self.paused_at.is_some() => mouse_area(
+                container(text("Paused, click to resume").size(20))
+                    .center(Fill)
+                    .style(container::dark),
+            )
+            .on_press(Message::Resume)
+            .into(),
+            Some(analysis) if self.show_analysis => analysis.view(),
+            _ => Canvas::new(self).width(Fill).height(Fill).into(),
+        };
+
+        column![self.header(), board].into()
+    }
+
+    fn header(&self) -> Element<'_, Message> {
+        if self.status() == GameStatus::Lost {
+            let metrics = self.metrics();
+
+            return row![
+                text("Game over")
+                    .size(14)
+                    .color(Color::from_rgb8(0xff, 0x40, 0x40)),
+                column![
+                    text!(
+                        "Time: {}  3BV: {}/{}",
+                        self.elapsed().as_secs(),
+                        metrics.solved_three_bv,
+                        metrics.three_bv
+                    )
+                    .size(12),
+                    text!(
+                        "{:.2} 3BV/s  {:.0}% efficiency  Clicks: {}L {}R {}C",
+                        metrics.per_second(),
+                        metrics.efficiency(),
+                        metrics.clicks.left,
+                        metrics.clicks.right,
+                        metrics.clicks.chord
+                    )
+                    .size(12),
+                ],
+                horizontal_space(),
+                self.face(),
+                horizontal_space(),
+            ]
+            .push_maybe(
+                self.can_undo()
+                    .then(|| button(text("Undo").size(14)).on_press(Message::Undo)),
+            )
+            .push_maybe(self.analysis.as_ref().map(|_| {
+                button(
+                    text(if self.show_analysis {
+                        "Board"
+                    } else {
+                        "Analysis"
+                    })
+                    .size(12),
+                )
+                .on_press(Message::ToggleAnalysis)
+            }))
+            .push(
+                button(text("Restart").size(14))
+                    .on_press_maybe(self.restartable.then_some(Message::Restart)),
+            )
+            .spacing(8)
+            .padding(4)
+            .height(HEADER_HEIGHT)
+            .align_y(Center)
+            .into();
+        }
+
+        let progress = self.progress();
+
+        row![
+            text!("Mines: {}", self.mines_remaining()).size(14),
+            text!("Safe: {}", self.safe_cells_hidden()).size(14),
+            text!("Time: {}", self.elapsed().as_secs()).size(14),
+            text!("Moves: {}", self.moves).size(14),
+            row![
+                progress_bar(0.0..=1.0, progress).width(60).height(8),
+                text!("{}%", (progress * 100.0).floor()).size(12),
+            ]
+            .spacing(4)
+            .align_y(Center),
+        ]
+        .push_maybe(
+            self.settings
+                .input
+                .flag_lock
+                .then(|| text("Flags locked").size(12)),
+        )
+        .push_maybe(self.was_insured().then(|| text("Mine defused").size(12)))
+        .push_maybe(self.no_hint_found.then(|| text("No safe cell").size(12)))
+        .push(horizontal_space())
+        .push(self.face())
+        .push(horizontal_space())
+        .push(
+            button(text("Open the rest").size(14))
+                .on_press_maybe(self.board.can_open_rest().then_some(Message::OpenRest)),
+        )
+        .spacing(8)
+        .padding(4)
+        .height(HEADER_HEIGHT)
+        .align_y(Center)
+        .into()
+    }
+
+    /// The face above the board, which starts a new game when clicked.
+    fn face(&self) -> Element<'_, Message> {
+        let face = match self.status() {
+            GameStatus::Won => Face::Cool,
+            GameStatus::Lost => Face::Dead,
+            GameStatus::InProgress if self.pressing => Face::Worried,
+            GameStatus::InProgress => Face::Smiling,
+        };
+
+        button(Canvas::new(face).width(FACE_SIZE).height(FACE_SIZE))
+            .on_press_maybe(self.restartable.then_some(Message::Restart))
+            .padding(2)
+            .into()
+    }
+
+    fn click_message(&self, button: Button, position: Position) -> Option<Message> {
+        let is_revealed = self
+            .board
+            .get(position)
+            .is_some_and(|cell| cell.is_revealed());
+
+        match button {
+            Button::Left if is_revealed && self.settings.input.left_click_chords => {
+                Some(Message::RevealSurrounding(position))
+            }
+            Button::Left => Some(Message::Reveal(position)),
+            Button::Right if is_revealed && self.settings.input.right_click_chords => {
+                Some(Message::RevealSurrounding(position))
+            }
+            Button::Right => Some(Message::ToggleMark(position)),
+            Button::Middle => Some(Message::RevealSurrounding(position)),
+            _ => None,
+        }
+    }
+}
+
+/// The distinct looks of a cell's background. Cells sharing a tile are drawn
+/// as a single path.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Tile {
+    Hidden,
+    Revealed,
+    Mine,
     Flag,
     QuestionMark,
 }
 
-impl Default for Marking {
-    fn default() -> Self {
-        Self::None
+impl Tile {
+    const ALL: [Tile; 5] = [
+        Tile::Hidden,
+        Tile::Revealed,
+        Tile::Mine,
+        Tile::Flag,
+        Tile::QuestionMark,
+    ];
+
+    fn of(cell: Cell) -> Self {
+        match (cell.is_revealed(), cell.marking(), cell.cell_type()) {
+            (true, _, CellType::Mine { .. }) => Tile::Mine,
+            (true, _, CellType::NonMine { .. }) => Tile::Revealed,
+            (false, Marking::Flag, _) => Tile::Flag,
+            (false, Marking::QuestionMark, _) => Tile::QuestionMark,
+            _ => Tile::Hidden,
+        }
     }
-}
 
-impl Marking {
-    pub fn next(self) -> Self {
+    fn color(self, theme: &BoardTheme) -> Color {
         match self {
-            Marking::None => Marking::Flag,
-            Marking::Flag => Marking::QuestionMark,
-            Marking::QuestionMark => Marking::None,
+            Tile::Hidden => theme.covered,
+            Tile::Revealed => theme.revealed,
+            Tile::Mine => theme.mine,
+            Tile::Flag => theme.flag,
+            Tile::QuestionMark => theme.question_mark,
         }
     }
+
+    /// Covered cells stand out from the board, revealed ones are sunk into it.
+    fn is_raised(self) -> bool {
+        matches!(self, Tile::Hidden | Tile::Flag | Tile::QuestionMark)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct Position {
-    row: i32,
-    column: i32,
+/// The shaded edges of classic tiles: lit from the top left on raised cells
+/// and from the bottom right on sunken ones.
+#[derive(Default)]
+struct Bevels {
+    light: path::Builder,
+    dark: path::Builder,
 }
 
-impl Position {
-    fn new(row: usize, column: usize) -> Self {
+impl Bevels {
+    /// How far the edges of raised and sunken cells reach towards the centre,
+    /// as a fraction of the distance.
+    const RAISED: f32 = 0.25;
+    const SUNKEN: f32 = 0.08;
+
+    fn add(&mut self, topology: &dyn Topology, position: Position, raised: bool) {
+        let centre = topology.centre(position);
+        let corners = topology.corners(position);
+        let depth = if raised { Self::RAISED } else { Self::SUNKEN };
+        let inset = |corner: Point| corner + (centre - corner) * depth;
+
+        for (i, &from) in corners.iter().enumerate() {
+            let to = corners[(i + 1) % corners.len()];
+            // Corners run clockwise, so the outward normal of the edge is the
+            // direction turned left.
+            let facing_top_left = (to.y - from.y) - (to.x - from.x) < 0.0;
+            let builder = if facing_top_left == raised {
+                &mut self.light
+            } else {
+                &mut self.dark
+            };
+
+            builder.move_to(from);
+            builder.line_to(to);
+            builder.line_to(inset(to));
+            builder.line_to(inset(from));
+            builder.close();
+        }
+    }
+
+    fn draw(self, frame: &mut Frame) {
+        frame.fill(&self.light.build(), Color::from_rgba(1.0, 1.0, 1.0, 0.5));
+        frame.fill(&self.dark.build(), Color::from_rgba(0.0, 0.0, 0.0, 0.35));
+    }
+}
+
+/// Numbers take their colour from the palette, or stand out from it when
+/// drawn on chips. Other glyphs share one colour.
+fn glyph_color(theme: &BoardTheme, cell: Cell, patterns: bool) -> Color {
+    match cell.number() {
+        Some(_) if cell.is_revealed() && patterns => theme.revealed,
+        Some(number) if cell.is_revealed() => theme.numbers.color(number),
+        _ => theme.glyph,
+    }
+}
+
+/// Text for every glyph a cell can display, laid out once around the origin
+/// and only moved into place while drawing.
+struct Glyphs {
+    digits: [Text; MAX_NEIGHBOURING_MINES],
+    mines: [Text; Grid::MAX_MINES_PER_CELL as usize],
+    flags: [Text; Grid::MAX_MINES_PER_CELL as usize],
+    question_mark: Text,
+}
+
+/// Eight neighbours, each holding as many mines as a cell can.
+const MAX_NEIGHBOURING_MINES: usize = 8 * Grid::MAX_MINES_PER_CELL as usize;
+
+static GLYPHS: LazyLock<Glyphs> = LazyLock::new(Glyphs::new);
+
+/// The tile colour, glyph colour, glyph and meaning of every kind of cell, as
+/// drawn on the board.
+pub fn legend(theme: &BoardTheme) -> [(Color, Color, &'static str, &'static str); 5] {
+    let glyphs = LazyLock::force(&GLYPHS);
+
+    [
+        (Tile::Hidden.color(theme), theme.glyph, "", "Hidden cell"),
+        (
+            Tile::Revealed.color(theme),
+            theme.numbers.color(3),
+            &glyphs.digits[2].content,
+            "Safe cell and its number of neighbouring mines",
+        ),
+        (
+            Tile::Flag.color(theme),
+            theme.glyph,
+            &glyphs.flags[0].content,
+            "Flag",
+        ),
+        (
+            Tile::QuestionMark.color(theme),
+            theme.glyph,
+            &glyphs.question_mark.content,
+            "Question mark",
+        ),
+        (
+            Tile::Mine.color(theme),
+            theme.glyph,
+            &glyphs.mines[0].content,
+            "Mine",
+        ),
+    ]
+}
+
+impl Glyphs {
+    fn new() -> Self {
+        let glyph = |content: &str| Text {
+            content: content.to_owned(),
+            size: if content.chars().count() > 2 {
+                0.45
+            } else {
+                0.7
+            }
+            .into(),
+            horizontal_alignment: iced::alignment::Horizontal::Center,
+            vertical_alignment: iced::alignment::Vertical::Center,
+            ..Default::default()
+        };
+
         Self {
-            row: row as i32,
-            column: column as i32,
+            digits: std::array::from_fn(|i| glyph(&(i + 1).to_string())),
+            mines: std::array::from_fn(|i| glyph(&"•".repeat(i + 1))),
+            flags: std::array::from_fn(|i| glyph(&"!".repeat(i + 1))),
+            question_mark: glyph("?"),
         }
     }
 
-    fn at(point: Point) -> Self {
-        Self {
-            row: (point.y / 32.0).floor() as i32,
-            column: (point.x / 32.0).floor() as i32,
+    fn get(&self, cell: Cell) -> Option<&Text> {
+        match (cell.is_revealed(), cell.marking(), cell.cell_type()) {
+            (true, _, CellType::Mine { count }) => self.mines.get(usize::from(count) - 1),
+            (true, _, CellType::NonMine { .. }) => match cell.number() {
+                Some(number) if number > 0 => self.digits.get(number - 1),
+                _ => None,
+            },
+            (false, Marking::Flag, _) => self.flags.get(usize::from(cell.flags()) - 1),
+            (false, Marking::QuestionMark, _) => Some(&self.question_mark),
+            _ => None,
         }
     }
+}
 
-    fn neighbours(&self) -> impl Iterator<Item = Position> {
-        iproduct!(-1..=1, -1..=1)
-            .filter(|&(x, y)| x != 0 || y != 0)
-            .map(|(x, y)| Position {
-                row: self.row + y,
-                column: self.column + x,
-            })
-    }
+#[derive(Default, Clone, Copy, Debug)]
+pub enum InteractionState {
+    #[default]
+    None,
+    Pressed(Button, Position),
+    /// Left and right are held together; releasing either chords.
+    Chording(Position),
+    /// One button of a chord was released, so the other's release is ignored.
+    ChordReleased,
+    /// The board follows the cursor, last seen at this point.
+    Panning(Point),
 }
 
-#[derive(Clone, Debug)]
-pub struct GameState {
-    cells: HashMap<Position, Cell>,
-    width: usize,
-    height: usize,
-    mines: usize,
-    has_revealed_any: bool,
+#[derive(Default, Debug)]
+pub struct CanvasState {
+    interaction: InteractionState,
+    pressed_at: Option<Instant>,
+    pressed_point: Point,
+    viewport: Viewport,
+    modifiers: keyboard::Modifiers,
+    keyboard_cursor: Option<Position>,
+    fingers: HashSet<touch::Finger>,
+    touch: Option<TouchGesture>,
+    debug_overlay: DebugOverlay,
+    cells: canvas::Cache,
+    /// The game revision, viewport and cascade wave the cached cells were
+    /// drawn for.
+    cells_drawn: cell::Cell<Option<(u64, Viewport, Option<u32>)>>,
 }
 
+/// A touch on the board, started by the first finger to land on it.
 #[derive(Clone, Copy, Debug)]
-pub enum Message {
-    Reveal(Position),
-    ToggleMark(Position),
-    RevealSurrounding(Position),
+struct TouchGesture {
+    finger: touch::Finger,
+    position: Position,
+    started_at: Instant,
+    /// A second finger joined, turning the tap into a chord.
+    chord: bool,
 }
 
 impl GameState {
-    pub fn new(width: usize, height: usize, mines: usize) -> Self {
-        let cells = HashMap::from_iter(
-            (0..width)
-                .flat_map(|c| (0..height).map(move |r| (Position::new(r, c), Cell::default()))),
-        );
+    /// Moves the keyboard cursor with the arrow keys and acts on the cell
+    /// under it with the bound reveal, flag and chord keys.
+    fn keyboard_input(
+        &self,
+        state: &mut CanvasState,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> (event::Status, Option<Message>) {
+        let movement = match key.as_ref() {
+            keyboard::Key::Named(Named::ArrowUp) => Some((-1, 0)),
+            keyboard::Key::Named(Named::ArrowDown) => Some((1, 0)),
+            keyboard::Key::Named(Named::ArrowLeft) => Some((0, -1)),
+            keyboard::Key::Named(Named::ArrowRight) => Some((0, 1)),
+            _ => None,
+        };
 
-        Self {
-            width,
-            height,
-            cells,
-            mines,
-            has_revealed_any: false,
-        }
-    }
-
-    fn initialize_state(&mut self, starting_position: Position) {
-        let mut rng = rand::rng();
-        let start_neighbors: Vec<_> = starting_position.neighbours().collect();
-        let mine_positions = self
-            .cells
-            .keys()
-            .filter(|p| **p != starting_position && !start_neighbors.contains(p))
-            .map(|p| p.clone())
-            .choose_multiple(&mut rng, self.mines);
-
-        for p in mine_positions {
-            self.cells.insert(p, Cell::mine());
-
-            for neighbor in p.neighbours() {
-                let cell = self.cells.get_mut(&neighbor);
-                if let Some(state) = cell {
-                    if let CellType::NonMine { neighbours } = &mut state.cell_type {
-                        *neighbours += 1;
-                    }
-                }
-            }
+        if let Some((rows, columns)) = movement {
+            let grid = self.board.grid();
+            state.keyboard_cursor = Some(match state.keyboard_cursor {
+                Some(cursor) => Position::new(
+                    (cursor.row() + rows).clamp(0, grid.height as i32 - 1) as usize,
+                    (cursor.column() + columns).clamp(0, grid.width as i32 - 1) as usize,
+                ),
+                None => self.board.central_cell(),
+            });
+
+            return (event::Status::Captured, None);
         }
+
+        let Some(cursor) = state.keyboard_cursor else {
+            return (event::Status::Ignored, None);
+        };
+
+        let message = match self.settings.keys.action(key) {
+            Some(Action::Reveal) => self.click_message(Button::Left, cursor),
+            Some(Action::Flag) if modifiers.shift() => Some(Message::UnlockMark(cursor)),
+            Some(Action::Flag) => Some(Message::ToggleMark(cursor)),
+            Some(Action::Chord) => Some(Message::RevealSurrounding(cursor)),
+            _ => return (event::Status::Ignored, None),
+        };
+
+        (event::Status::Captured, message)
     }
 
-    fn reveal(&mut self, position: &Position) {
-        let cell = self.cells.get_mut(&position);
-        if let Some(cell) = cell {
-            if let Cell {
-                is_revealed: false,
-                marking: Marking::None,
-                ..
-            } = cell
+    /// Zooms with the scroll wheel, Ctrl+= and Ctrl+-, resets the view with
+    /// Ctrl+0 and pans while the right or middle button is dragged.
+    fn viewport_input(
+        &self,
+        state: &mut CanvasState,
+        event: &Event,
+        bounds: iced::Rectangle,
+        cursor: iced::advanced::mouse::Cursor,
+    ) -> event::Status {
+        let size = bounds.size();
+
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let Some(point) = cursor.position_in(bounds) else {
+                    return event::Status::Ignored;
+                };
+
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / self.cell_size(),
+                };
+                self.zoom(&mut state.viewport, size, point, ZOOM_STEP.powf(lines));
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if modifiers.command() =>
             {
-                cell.is_revealed = true;
+                let centre = Point::new(size.width / 2.0, size.height / 2.0);
 
-                if let CellType::NonMine { neighbours: 0 } = cell.cell_type {
-                    for n in position.neighbours() {
-                        self.reveal(&n);
+                match key.as_ref() {
+                    keyboard::Key::Character("=" | "+") => {
+                        self.zoom(&mut state.viewport, size, centre, ZOOM_STEP);
+                    }
+                    keyboard::Key::Character("-") => {
+                        self.zoom(&mut state.viewport, size, centre, 1.0 / ZOOM_STEP);
                     }
+                    keyboard::Key::Character("0") => state.viewport = Viewport::default(),
+                    _ => return event::Status::Ignored,
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => match state.interaction {
+                InteractionState::Panning(last) => {
+                    self.pan(&mut state.viewport, size, *position - last);
+                    state.interaction = InteractionState::Panning(*position);
+                }
+                InteractionState::Pressed(Button::Right | Button::Middle, _)
+                    if position.distance(state.pressed_point) > PAN_THRESHOLD =>
+                {
+                    self.pan(&mut state.viewport, size, *position - state.pressed_point);
+                    state.interaction = InteractionState::Panning(*position);
+                    state.pressed_at = None;
                 }
+                _ => return event::Status::Ignored,
+            },
+            Event::Mouse(mouse::Event::ButtonReleased(_))
+                if matches!(state.interaction, InteractionState::Panning(_)) =>
+            {
+                state.interaction = InteractionState::None;
             }
+            _ => return event::Status::Ignored,
         }
-    }
 
-    fn toggle_mark(&mut self, position: &Position) {
-        let cell = self.cells.get_mut(&position);
-        if let Some(cell) = cell {
-            cell.marking = cell.marking.next();
-        }
+        event::Status::Captured
     }
 
-    fn reveal_surrounding(&mut self, position: &Position) {
-        let cell = self.cells.get(&position);
-        if let Some(&Cell {
-            is_revealed: true,
-            cell_type: CellType::NonMine { neighbours },
-            ..
-        }) = cell
-        {
-            let (marked, unmarked): (Vec<_>, Vec<_>) =
-                position.neighbours().partition(|position| {
-                    matches!(
-                        self.cells.get(position),
-                        Some(&Cell {
-                            is_revealed: false,
-                            marking: Marking::Flag,
-                            ..
-                        })
-                    )
-                });
+    /// Taps reveal, long presses toggle a flag and tapping with two fingers
+    /// chords on the cell the first finger touched.
+    fn touch_input(
+        &self,
+        state: &mut CanvasState,
+        event: touch::Event,
+        bounds: iced::Rectangle,
+    ) -> (event::Status, Option<Message>) {
+        let layout = self.layout(bounds.size(), state.viewport);
+        let cell_at = |point: Point| {
+            bounds
+                .contains(point)
+                .then(|| layout.position(Point::ORIGIN + (point - bounds.position())))
+        };
+
+        match event {
+            touch::Event::FingerPressed { id, position } => {
+                state.fingers.insert(id);
+
+                match &mut state.touch {
+                    Some(gesture) => {
+                        gesture.chord = true;
+                        state.interaction = InteractionState::Chording(gesture.position);
+                    }
+                    None if let Some(position) = cell_at(position) => {
+                        state.interaction = InteractionState::Pressed(Button::Left, position);
+                        state.touch = Some(TouchGesture {
+                            finger: id,
+                            position,
+                            started_at: Instant::now(),
+                            chord: false,
+                        });
+                    }
+                    None => return (event::Status::Ignored, None),
+                }
+
+                (event::Status::Captured, None)
+            }
+            touch::Event::FingerMoved { id, position } => {
+                if let Some(gesture) = state.touch
+                    && gesture.finger == id
+                    && cell_at(position) != Some(gesture.position)
+                {
+                    state.touch = None;
+                    state.interaction = InteractionState::None;
+                }
+
+                (event::Status::Ignored, None)
+            }
+            touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. } => {
+                state.fingers.remove(&id);
 
-            if marked.len() == neighbours {
-                for n in unmarked {
-                    self.reveal(&n);
+                if !state.fingers.is_empty() {
+                    return (event::Status::Captured, None);
                 }
+
+                state.interaction = InteractionState::None;
+
+                let Some(gesture) = state.touch.take() else {
+                    return (event::Status::Ignored, None);
+                };
+
+                let message = if matches!(event, touch::Event::FingerLost { .. }) {
+                    None
+                } else if gesture.chord {
+                    Some(Message::RevealSurrounding(gesture.position))
+                } else if gesture.started_at.elapsed() >= LONG_PRESS_DURATION {
+                    match self.click_message(Button::Right, gesture.position) {
+                        Some(Message::ToggleMark(position)) => Some(Message::UnlockMark(position)),
+                        message => message,
+                    }
+                } else {
+                    self.click_message(Button::Left, gesture.position)
+                };
+
+                (event::Status::Captured, message)
             }
         }
     }
 
-    pub fn update(&mut self, message: Message) {
-        match message {
-            Message::Reveal(position) => {
-                if !self.has_revealed_any {
-                    self.initialize_state(position);
-                    self.has_revealed_any = true;
+    /// Handles input on the board canvas.
+    fn input(
+        &self,
+        state: &mut CanvasState,
+        event: canvas::Event,
+        bounds: iced::Rectangle,
+        cursor: iced::advanced::mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            state.modifiers = modifiers;
+
+            return (event::Status::Ignored, None);
+        }
+
+        if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = &event {
+            match key.as_ref() {
+                keyboard::Key::Named(Named::F12) => {
+                    state.debug_overlay.toggle();
+
+                    return (event::Status::Captured, None);
+                }
+                keyboard::Key::Character("z" | "Z") if modifiers.command() && modifiers.shift() => {
+                    return (event::Status::Captured, Some(Message::Redo));
                 }
+                keyboard::Key::Character("z" | "Z") if modifiers.command() => {
+                    return (event::Status::Captured, Some(Message::Undo));
+                }
+                keyboard::Key::Character("y" | "Y") if modifiers.command() => {
+                    return (event::Status::Captured, Some(Message::Redo));
+                }
+                _ => {}
+            }
+
+            let message = match self.settings.keys.action(key) {
+                Some(Action::Probabilities) => Some(Message::ToggleProbabilities),
+                Some(Action::Hint) if !self.is_over() => Some(Message::Hint),
+                Some(Action::OpenRest) if self.board.can_open_rest() => Some(Message::OpenRest),
+                _ => None,
+            };
 
-                self.reveal(&position);
+            if message.is_some() {
+                return (event::Status::Captured, message);
             }
-            Message::ToggleMark(position) => self.toggle_mark(&position),
-            Message::RevealSurrounding(position) => self.reveal_surrounding(&position),
         }
-    }
 
-    pub fn view(&self) -> Element<Message> {
-        Canvas::new(self).width(Fill).height(Fill).into()
-    }
-}
+        if self.viewport_input(state, &event, bounds, cursor) == event::Status::Captured {
+            return (event::Status::Captured, None);
+        }
 
-#[derive(Default, Clone, Copy, Debug)]
-pub enum InteractionState {
-    #[default]
-    None,
-    Pressed(Button, Position),
+        if self.is_over() {
+            return (event::Status::Ignored, None);
+        }
+
+        if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = &event {
+            return self.keyboard_input(state, key, *modifiers);
+        }
+
+        if let Event::Touch(event) = event {
+            return self.touch_input(state, event, bounds);
+        }
+
+        let Some(cursor_position) = cursor.position_in(bounds) else {
+            return (event::Status::Ignored, None);
+        };
+
+        let position = self
+            .layout(bounds.size(), state.viewport)
+            .position(cursor_position);
+        let current_state = state.interaction;
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(button)) => {
+                state.interaction = match (current_state, button) {
+                    (InteractionState::Pressed(Button::Left, _), Button::Right)
+                    | (InteractionState::Pressed(Button::Right, _), Button::Left) => {
+                        InteractionState::Chording(position)
+                    }
+                    _ => InteractionState::Pressed(button, position),
+                };
+                state.pressed_at = Some(Instant::now());
+                state.pressed_point = cursor.position().unwrap_or(Point::ORIGIN);
+
+                (event::Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left | Button::Right))
+                if matches!(current_state, InteractionState::Chording(_)) =>
+            {
+                state.interaction = InteractionState::ChordReleased;
+                state.pressed_at = None;
+
+                let message =
+                    matches!(current_state, InteractionState::Chording(p) if p == position)
+                        .then_some(Message::RevealSurrounding(position));
+
+                (event::Status::Captured, message)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(_))
+                if matches!(current_state, InteractionState::ChordReleased) =>
+            {
+                state.interaction = InteractionState::None;
+
+                (event::Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(button)) => {
+                state.interaction = InteractionState::None;
+                let is_long_press = state
+                    .pressed_at
+                    .take()
+                    .is_some_and(|at| at.elapsed() >= LONG_PRESS_DURATION);
+
+                if matches!(current_state, InteractionState::Pressed(b, p) if b == button && p == position)
+                {
+                    let message = match self.click_message(button, position) {
+                        Some(Message::ToggleMark(position))
+                            if state.modifiers.shift() || is_long_press =>
+                        {
+                            Some(Message::UnlockMark(position))
+                        }
+                        message => message,
+                    };
+
+                    (event::Status::Captured, message)
+                } else {
+                    (event::Status::Ignored, None)
+                }
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
 }
 
 impl canvas::Program<Message> for GameState {
-    type State = InteractionState;
+    type State = CanvasState;
 
     fn draw(
         &self,
         state: &Self::State,
         renderer: &Renderer,
-        theme: &Theme,
+        _theme: &Theme,
         bounds: iced::Rectangle,
         cursor: iced::advanced::mouse::Cursor,
     ) -> Vec<canvas::Geometry<Renderer>> {
-        let cells = {
-            let mut frame = Frame::new(renderer, bounds.size());
-            frame.fill_rectangle(
-                Point::ORIGIN,
-                frame.size(),
-                Color::from_rgb8(0x20, 0x20, 0x20),
-            );
+        let draw_start = Instant::now();
+        let theme = self.settings.appearance.theme();
+        let patterns = self.settings.accessibility.patterns;
+        // Patterns would sit underneath the images, so they keep to the vector
+        // style, as do the shapes the atlas has no tiles for.
+        let sprites = sprites::get(&self.settings.skin).filter(|_| {
+            self.settings.tiles == TileStyle::Classic
+                && self.board.grid().shape == Shape::Square
+                && !patterns
+        });
+        let sprite = |cell| sprites.and_then(|sprites| sprites.tile(cell));
+        let layout = self.layout(bounds.size(), state.viewport);
+        let topology = self.topology();
+        let outline = |position| Path::new(|builder| topology.outline(builder, position));
+        let visible = layout.visible_area(bounds.size(), self.board.grid());
+        let wave = self
+            .cascade
+            .as_ref()
+            .map(|cascade| (cascade, cascade.wave(Instant::now())));
+        // Cells the cascade has not reached yet are drawn covered.
+        let visible_cells = || {
+            visible.positions().filter_map(move |position| {
+                let cell = match wave {
+                    Some((cascade, wave)) if cascade.hides(position, wave) => Cell::default(),
+                    _ => self.board.get(position)?,
+                };
+
+                Some((position, cell))
+            })
+        };
+
+        let drawn = Some((self.revision, state.viewport, wave.map(|(_, wave)| wave)));
+
+        let cache_rebuilt = state.cells_drawn.replace(drawn) != drawn;
+        if cache_rebuilt {
+            state.cells.clear();
+        }
+
+        let cells = state.cells.draw(renderer, bounds.size(), |frame| {
+            frame.fill_rectangle(Point::ORIGIN, frame.size(), theme.background);
 
             frame.with_save(|frame| {
-                frame.scale(32.0);
-
-                for (position, cell) in &self.cells {
-                    let (color, text): (Color, Option<String>) = match cell {
-                        Cell {
-                            is_revealed: true,
-                            cell_type: CellType::Mine,
-                            ..
-                        } => (Color::from_rgb8(0xff, 0, 0), Some("•".to_owned())),
-                        Cell {
-                            is_revealed: true,
-                            cell_type: CellType::NonMine { neighbours },
-                            ..
-                        } if *neighbours > 0 => (
-                            Color::from_rgb8(0xff, 0xff, 0xff),
-                            Some(format!("{neighbours}")),
-                        ),
-                        Cell {
-                            is_revealed: true,
-                            cell_type: CellType::NonMine { neighbours: 0 },
-                            ..
-                        } => (Color::from_rgb8(0xff, 0xff, 0xff), None),
-                        Cell {
-                            is_revealed: false,
-                            marking: Marking::Flag,
-                            ..
-                        } => (Color::from_rgb8(0xff, 0x30, 0x10), Some("!".to_owned())),
-                        Cell {
-                            is_revealed: false,
-                            marking: Marking::QuestionMark,
-                            ..
-                        } => (Color::from_rgb8(0x20, 0x80, 0x40), Some("?".to_owned())),
-                        _ => (Color::from_rgb8(0x40, 0x40, 0x40), None),
-                    };
+                layout.apply(frame);
+
+                let mut tiles: [path::Builder; Tile::ALL.len()] =
+                    std::array::from_fn(|_| path::Builder::new());
+                let mut bevels = Bevels::default();
+
+                for (position, cell) in visible_cells() {
+                    if let Some(image) = sprite(cell) {
+                        let corner = topology.centre(position) - Vector::new(0.5, 0.5);
+                        frame.draw_image(iced::Rectangle::new(corner, Size::UNIT), image.clone());
+                    } else {
+                        let tile = Tile::of(cell);
+                        topology.outline(&mut tiles[tile as usize], position);
+
+                        if self.settings.tiles == TileStyle::Classic {
+                            bevels.add(topology, position, tile.is_raised());
+                        }
+                    }
+                }
+
+                for (tile, builder) in Tile::ALL.into_iter().zip(tiles) {
+                    frame.fill(&builder.build(), tile.color(&theme));
+                }
 
-                    let position = Point::new(position.column as f32, position.row as f32);
-                    frame.fill_rectangle(position, Size::UNIT, color);
-                    let position = Point::new(position.x + 0.5, position.y + 0.5);
+                bevels.draw(frame);
+
+                if patterns {
+                    patterns::draw(frame, topology, visible_cells(), &theme);
+                }
+
+                for (position, cell) in visible_cells() {
+                    if sprite(cell).is_some() || patterns && patterns::replaces_glyph(cell) {
+                        continue;
+                    }
 
-                    if let Some(content) = text {
+                    if let Some(glyph) = GLYPHS.get(cell) {
                         frame.fill_text(Text {
-                            content,
-                            position,
-                            size: 0.7.into(),
-                            color: Color::BLACK,
+                            position: topology.centre(position),
+                            color: glyph_color(&theme, cell, patterns),
+                            ..glyph.clone()
+                        });
+                    }
+                }
+            });
+        });
+
+        let probabilities =
+            self.probabilities
+                .as_ref()
+                .filter(|_| !self.is_over())
+                .map(|probabilities| {
+                    let mut frame = Frame::new(renderer, bounds.size());
+                    layout.apply(&mut frame);
+
+                    let visible_probabilities = visible
+                        .positions()
+                        .filter_map(|position| Some((position, *probabilities.get(&position)?)));
+
+                    for (position, probability) in visible_probabilities {
+                        frame.fill(
+                            &outline(position),
+                            Color::from_rgba(probability, 1.0 - probability, 0.0, 0.45),
+                        );
+                        frame.fill_text(Text {
+                            content: format!("{:.0}", probability * 100.0),
+                            position: topology.centre(position),
+                            size: 0.35.into(),
+                            color: Color::WHITE,
                             horizontal_alignment: iced::alignment::Horizontal::Center,
                             vertical_alignment: iced::alignment::Vertical::Center,
                             ..Default::default()
                         });
                     }
-                }
-            });
 
-            frame.into_geometry()
-        };
+                    frame.into_geometry()
+                });
 
         let overlay = {
             let mut frame = Frame::new(renderer, bounds.size());
-            frame.scale(32.0);
-
-            if let InteractionState::Pressed(button, position) = *state {
-                match button {
-                    Button::Middle => {
-                        let neighbours = position
-                            .neighbours()
-                            .flat_map(|n| self.cells.get_key_value(&n))
-                            .filter_map(|(position, cell)| {
-                                if matches!(
-                                    cell,
-                                    Cell {
-                                        is_revealed: false,
-                                        marking: Marking::None,
-                                        ..
-                                    }
-                                ) {
-                                    Some(position)
-                                } else {
-                                    None
-                                }
-                            });
+            layout.apply(&mut frame);
+
+            if let Some(revealed_at) = self.revealed_at {
+                let fade =
+                    1.0 - revealed_at.elapsed().as_secs_f32() / HIGHLIGHT_DURATION.as_secs_f32();
+
+                if fade > 0.0 {
+                    let highlight = Path::new(|builder| {
+                        for &position in &self.recently_revealed {
+                            let hidden =
+                                wave.is_some_and(|(cascade, wave)| cascade.hides(position, wave));
+
+                            if visible.contains(position) && !hidden {
+                                topology.outline(builder, position);
+                            }
+                        }
+                    });
+
+                    frame.fill(
+                        &highlight,
+                        Color {
+                            a: theme.highlight.a * fade,
+                            ..theme.highlight
+                        },
+                    );
+                }
+            }
+
+            if let Some(analysis) = &self.analysis {
+                if let Some(fatal) = analysis.fatal {
+                    frame.stroke(
+                        &outline(fatal),
+                        Stroke::default().with_width(0.1).with_color(theme.fatal),
+                    );
+                }
+
+                let crosses = Path::new(|builder| {
+                    for position in &analysis.wrong_flags {
+                        let centre = topology.centre(*position);
+                        builder.move_to(centre + Vector::new(-0.3, -0.3));
+                        builder.line_to(centre + Vector::new(0.3, 0.3));
+                        builder.move_to(centre + Vector::new(0.3, -0.3));
+                        builder.line_to(centre + Vector::new(-0.3, 0.3));
+                    }
+                });
+
+                frame.stroke(
+                    &crosses,
+                    Stroke::default()
+                        .with_width(0.08)
+                        .with_color(theme.wrong_flag),
+                );
+            }
+
+            if let Some(cursor) = state.keyboard_cursor
+                && !self.is_over()
+            {
+                frame.stroke(
+                    &outline(cursor),
+                    Stroke::default()
+                        .with_width(0.08)
+                        .with_color(theme.keyboard_cursor),
+                );
+            }
+
+            if let Some(hint) = self.hint {
+                frame.fill(&outline(hint), theme.hint);
+            }
+
+            for &position in &self.defused {
+                if self.board.get(position).is_some_and(|cell| !cell.is_mine()) {
+                    frame.stroke(
+                        &outline(position),
+                        Stroke::default().with_width(0.1).with_color(theme.fatal),
+                    );
+                }
+            }
+
+            let pressed = match state.interaction {
+                InteractionState::Pressed(button, position) => {
+                    Some((position, self.click_message(button, position)))
+                }
+                InteractionState::Chording(position) => {
+                    Some((position, Some(Message::RevealSurrounding(position))))
+                }
+                _ => None,
+            };
+
+            if let Some((position, message)) = pressed {
+                match message {
+                    Some(Message::RevealSurrounding(_)) => {
+                        let neighbours =
+                            self.board
+                                .grid()
+                                .neighbours(position)
+                                .into_iter()
+                                .filter(|&n| {
+                                    self.board.get(n).is_some_and(|cell| {
+                                        !cell.is_revealed() && cell.marking() == Marking::None
+                                    })
+                                });
 
                         for n in neighbours {
-                            let position = Point::new(n.column as f32, n.row as f32);
-                            frame.fill_rectangle(
-                                position,
-                                Size::UNIT,
-                                Color::from_rgb8(0x10, 0x10, 0x10),
-                            );
+                            frame.fill(&outline(n), theme.pressed);
                         }
                     }
                     _ => {
-                        if let Some(&Cell {
-                            is_revealed: false, ..
-                        }) = self.cells.get(&position)
+                        if self
+                            .board
+                            .get(position)
+                            .is_some_and(|cell| !cell.is_revealed())
                         {
-                            let position = Point::new(position.column as f32, position.row as f32);
-                            frame.fill_rectangle(
-                                position,
-                                Size::UNIT,
-                                Color::from_rgb8(0x10, 0x10, 0x10),
-                            );
+                            frame.fill(&outline(position), theme.pressed);
                         }
                     }
                 }
             } else {
                 let hovered_cell = cursor
                     .position_in(bounds)
-                    .map(|position| Position::at(position))
-                    .and_then(|position| self.cells.get_key_value(&position));
-
-                if let Some((
-                    &position,
-                    &Cell {
-                        is_revealed: false, ..
-                    },
-                )) = hovered_cell
+                    .map(|point| layout.position(point))
+                    .and_then(|position| Some((position, self.board.get(position)?)));
+
+                if let Some((position, cell)) = hovered_cell
+                    && !cell.is_revealed()
+                    && !self.is_over()
                 {
-                    let position = Point::new(position.column as f32, position.row as f32);
-                    frame.fill_rectangle(
-                        position,
-                        Size::UNIT,
-                        Color::from_rgba8(0xff, 0xff, 0xff, 0.5),
-                    );
+                    frame.fill(&outline(position), theme.hover);
                 }
             }
 
             frame.into_geometry()
         };
 
-        vec![cells, overlay]
+        let mut layers = vec![cells];
+        layers.extend(probabilities);
+        layers.push(overlay);
+
+        if let Some(burst) = &self.burst {
+            let mut frame = Frame::new(renderer, bounds.size());
+
+            match self.status() {
+                GameStatus::Won => burst.draw(&mut frame, Instant::now(), bounds.size()),
+                _ => {
+                    layout.apply(&mut frame);
+                    burst.draw(&mut frame, Instant::now(), Size::UNIT);
+                }
+            }
+
+            layers.push(frame.into_geometry());
+        }
+
+        if state.debug_overlay.is_visible() {
+            let stats = FrameStats {
+                draw_time: draw_start.elapsed(),
+                // The cells within the viewport, whether or not they came
+                // from the cache this frame.
+                cells_drawn: visible_cells().count(),
+                cache_rebuilt,
+                flags_placed: self.board.flags_placed(),
+                cells_revealed: self.board.cells_revealed(),
+                safe_cells_remaining: self.board.safe_cells_remaining(),
+                interaction: format!("{:?}", state.interaction),
+            };
+
+            layers.push(state.debug_overlay.draw(renderer, bounds.size(), stats));
+        }
+
+        layers
     }
 
     fn mouse_interaction(
@@ -400,27 +1989,31 @@ impl canvas::Program<Message> for GameState {
             return mouse::Interaction::default();
         };
 
-        let position = Position::at(cursor_position);
-        let cell = self.cells.get(&position);
+        if matches!(state.interaction, InteractionState::Panning(_)) {
+            return mouse::Interaction::Grabbing;
+        }
+
+        if self.is_over() {
+            return mouse::Interaction::Idle;
+        }
+
+        let position = self
+            .layout(bounds.size(), state.viewport)
+            .position(cursor_position);
+        let cell = self.board.get(position);
 
-        if let Some(&Cell {
-            is_revealed: false, ..
-        }) = cell
+        if cell.is_some_and(|cell| !cell.is_revealed()) {
+            mouse::Interaction::Pointer
+        } else if let InteractionState::Pressed(_, pressed_position)
+        | InteractionState::Chording(pressed_position) = state.interaction
+            && self
+                .board
+                .get(pressed_position)
+                .is_some_and(|cell| !cell.is_revealed())
         {
             mouse::Interaction::Pointer
         } else {
-            if let InteractionState::Pressed(_, pressed_position) = *state {
-                if let Some(&Cell {
-                    is_revealed: false, ..
-                }) = self.cells.get(&pressed_position)
-                {
-                    mouse::Interaction::Pointer
-                } else {
-                    mouse::Interaction::Idle
-                }
-            } else {
-                mouse::Interaction::Idle
-            }
+            mouse::Interaction::Idle
         }
     }
 
@@ -431,37 +2024,19 @@ impl canvas::Program<Message> for GameState {
         bounds: iced::Rectangle,
         cursor: iced::advanced::mouse::Cursor,
     ) -> (canvas::event::Status, Option<Message>) {
-        let Some(cursor_position) = cursor.position_in(bounds) else {
-            return (event::Status::Ignored, None);
-        };
-
-        let position = Position::at(cursor_position);
-        let current_state = *state;
-
-        match event {
-            Event::Mouse(mouse::Event::ButtonPressed(button)) => {
-                *state = InteractionState::Pressed(button, position);
-
-                (event::Status::Captured, None)
-            }
-            Event::Mouse(mouse::Event::ButtonReleased(button)) => {
-                *state = InteractionState::None;
-
-                if matches!(current_state, InteractionState::Pressed(b, p) if b == button && p == position)
-                {
-                    let message = match button {
-                        Button::Left => Some(Message::Reveal(position)),
-                        Button::Right => Some(Message::ToggleMark(position)),
-                        Button::Middle => Some(Message::RevealSurrounding(position)),
-                        _ => None,
-                    };
+        let (status, message) = self.input(state, event, bounds, cursor);
+        let pressing = matches!(
+            state.interaction,
+            InteractionState::Pressed(Button::Left, _) | InteractionState::Chording(_)
+        );
 
-                    (event::Status::Captured, message)
-                } else {
-                    (event::Status::Ignored, None)
-                }
+        // Moves clear the pressing state themselves, so the face only needs
+        // telling when nothing else is sent.
+        match message {
+            None if pressing != self.pressing => {
+                (event::Status::Captured, Some(Message::Pressing(pressing)))
             }
-            _ => (event::Status::Ignored, None),
+            message => (status, message),
         }
     }
 }
@@ -0,0 +1,271 @@
+use std::{
+    array, fmt, iter,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Position;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Shape {
+    #[default]
+    Square,
+    Hex,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 2] = [Shape::Square, Shape::Hex];
+
+    /// The cells touching `position`, including ones off the board.
+    /// Hexagons are pointy-topped, with odd rows shifted half a cell to the
+    /// right.
+    pub fn neighbours(self, position: Position) -> Neighbours {
+        let offsets: &[(i32, i32)] = match self {
+            Shape::Square => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+            Shape::Hex if position.row().rem_euclid(2) == 0 => {
+                &[(-1, -1), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 0)]
+            }
+            Shape::Hex => &[(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)],
+        };
+
+        offsets
+            .iter()
+            .map(|&(rows, columns)| position.offset(rows, columns))
+            .collect()
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Shape::Square => "Square cells",
+            Shape::Hex => "Hexagonal cells",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Which cells count as a cell's neighbours, for its number, chords and
+/// the flood of empty cells.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Neighbourhood {
+    /// The cells touching it, as given by its [`Shape`].
+    #[default]
+    Adjacent,
+    /// The eight cells a chess knight could jump to.
+    Knight,
+}
+
+impl Neighbourhood {
+    pub const ALL: [Neighbourhood; 2] = [Neighbourhood::Adjacent, Neighbourhood::Knight];
+
+    const KNIGHT_MOVES: [(i32, i32); 8] = [
+        (-2, -1),
+        (-2, 1),
+        (-1, -2),
+        (-1, 2),
+        (1, -2),
+        (1, 2),
+        (2, -1),
+        (2, 1),
+    ];
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Neighbourhood::Adjacent => "Adjacent neighbours",
+            Neighbourhood::Knight => "Knight-move neighbours",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// How much of the board the first reveal is sure to leave free of mines.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum FirstClick {
+    /// The first reveal can hit a mine like any other.
+    Anywhere,
+    SafeCell,
+    /// The first cell and its neighbours are free, so the first reveal
+    /// always opens an area.
+    #[default]
+    Opening,
+}
+
+impl FirstClick {
+    pub const ALL: [FirstClick; 3] = [
+        FirstClick::Anywhere,
+        FirstClick::SafeCell,
+        FirstClick::Opening,
+    ];
+}
+
+impl fmt::Display for FirstClick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FirstClick::Anywhere => "First click can hit a mine",
+            FirstClick::SafeCell => "First cell is safe",
+            FirstClick::Opening => "First click opens an area",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The cells a board is made of, how they connect and how many mines each
+/// one can hold.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub shape: Shape,
+    /// Whether neighbours continue across the edges, joining the top to the
+    /// bottom and the left to the right.
+    #[serde(default)]
+    pub wrap: bool,
+    #[serde(default = "Grid::single_mine")]
+    pub mines_per_cell: u8,
+    #[serde(default)]
+    pub first_click: FirstClick,
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
+    /// Whether every number is off by one, either way. Empty cells then
+    /// show a one and no longer open their neighbours.
+    #[serde(default)]
+    pub liar: bool,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl Grid {
+    pub const MAX_MINES_PER_CELL: u8 = 3;
+
+    fn single_mine() -> u8 {
+        1
+    }
+
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            shape: Shape::default(),
+            wrap: false,
+            mines_per_cell: Self::single_mine(),
+            first_click: FirstClick::default(),
+            neighbourhood: Neighbourhood::default(),
+            liar: false,
+        }
+    }
+
+    /// Whether this is the classic board, the only one best times are kept for.
+    pub fn is_classic(&self) -> bool {
+        self.shape == Shape::Square
+            && !self.wrap
+            && self.mines_per_cell == 1
+            && self.first_click == FirstClick::Opening
+            && self.neighbourhood == Neighbourhood::Adjacent
+            && !self.liar
+    }
+
+    /// The neighbours of `position`. Off-board cells are left in unless the
+    /// grid wraps, so lookups must still check for them.
+    pub fn neighbours(&self, position: Position) -> Neighbours {
+        let neighbours = match self.neighbourhood {
+            Neighbourhood::Adjacent => self.shape.neighbours(position),
+            Neighbourhood::Knight => Neighbourhood::KNIGHT_MOVES
+                .iter()
+                .map(|&(rows, columns)| position.offset(rows, columns))
+                .collect(),
+        };
+
+        if !self.wrap {
+            return neighbours;
+        }
+
+        let (rows, columns) = (self.height as i32, self.width as i32);
+        let mut wrapped = Neighbours::default();
+
+        // On very small boards a cell can wrap onto itself or reach the same
+        // neighbour from two sides.
+        for neighbour in neighbours {
+            let neighbour = Position::default().offset(
+                neighbour.row().rem_euclid(rows),
+                neighbour.column().rem_euclid(columns),
+            );
+
+            if neighbour != position && !wrapped.contains(&neighbour) {
+                wrapped.push(neighbour);
+            }
+        }
+
+        wrapped
+    }
+}
+
+/// The neighbours of a cell, kept inline since no cell has more than eight.
+/// Derefs to a slice of them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Neighbours {
+    cells: [Position; 8],
+    len: usize,
+}
+
+impl Neighbours {
+    fn push(&mut self, position: Position) {
+        self.cells[self.len] = position;
+        self.len += 1;
+    }
+}
+
+impl Deref for Neighbours {
+    type Target = [Position];
+
+    fn deref(&self) -> &[Position] {
+        &self.cells[..self.len]
+    }
+}
+
+impl DerefMut for Neighbours {
+    fn deref_mut(&mut self) -> &mut [Position] {
+        &mut self.cells[..self.len]
+    }
+}
+
+impl FromIterator<Position> for Neighbours {
+    fn from_iter<I: IntoIterator<Item = Position>>(positions: I) -> Self {
+        let mut neighbours = Self::default();
+
+        for position in positions {
+            neighbours.push(position);
+        }
+
+        neighbours
+    }
+}
+
+impl IntoIterator for Neighbours {
+    type Item = Position;
+    type IntoIter = iter::Take<array::IntoIter<Position, 8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter().take(self.len)
+    }
+}
@@ -0,0 +1,192 @@
+use iced::{
+    Alignment::Center,
+    Color, Element,
+    Length::Fill,
+    widget::{Column, button, column, container, row, scrollable, text},
+};
+
+use crate::{
+    game_state,
+    settings::{Action, Settings},
+};
+
+pub fn view<'a, Message: Clone + 'a>(
+    settings: &Settings,
+    on_close: Message,
+) -> Element<'a, Message> {
+    let mut controls = vec![
+        ("Left click", "Reveal a cell"),
+        (
+            "Right click",
+            if settings.marks.question_marks {
+                "Cycle flag / question mark / none"
+            } else {
+                "Toggle a flag"
+            },
+        ),
+        (
+            "Middle click",
+            "Reveal around a number whose flags are complete",
+        ),
+        (
+            "Left + right click",
+            "Reveal around a number, like middle click",
+        ),
+        ("Tap", "Reveal a cell"),
+        ("Long press", "Toggle a flag"),
+        ("Two-finger tap", "Reveal around a number"),
+        ("Scroll wheel or Ctrl+= / Ctrl+-", "Zoom in or out"),
+        ("Right or middle drag", "Pan a zoomed board"),
+        ("Ctrl+0", "Reset the zoom"),
+        ("Arrow keys", "Move the keyboard cursor"),
+        ("F1", "Show or hide this help"),
+        ("F2", "Start a fresh board of the same size"),
+    ];
+
+    if settings.input.left_click_chords {
+        controls.push(("Left click on a number", "Reveal around it"));
+    }
+
+    if settings.input.right_click_chords {
+        controls.push(("Right click on a number", "Reveal around it"));
+    }
+
+    if settings.input.flag_lock {
+        controls.push(("Shift + right click or hold", "Remove a locked flag"));
+    }
+
+    if settings.assists.undo {
+        controls.push(("Ctrl+Z", "Undo the last move"));
+        controls.push(("Ctrl+Y or Ctrl+Shift+Z", "Redo an undone move"));
+    }
+
+    let mut rules = Vec::new();
+
+    if settings.assists.auto_finish {
+        rules.push("Remaining mines are flagged automatically once every safe cell is open.");
+    }
+
+    if settings.input.no_flags {
+        rules.push("Flags are off: marking cells and chording do nothing.");
+    }
+
+    if settings.assists.auto_flag {
+        rules.push(
+            "Hidden cells around a number with exactly that many hidden neighbours are flagged automatically.",
+        );
+    }
+
+    if settings.assists.auto_open {
+        rules.push("Once every mine is flagged correctly, the remaining cells open by themselves.");
+    }
+
+    if settings.assists.insurance {
+        rules.push(
+            "The first mine hit is defused instead of ending the game, but the game no longer counts towards best times.",
+        );
+    }
+
+    if settings.assists.hint_penalty {
+        rules.push("Every hint adds 10 seconds to the clock.");
+    }
+
+    if settings.assists.undo {
+        rules.push("Games where a move was undone do not count towards best times.");
+    }
+
+    if settings.marks.question_marks_block_chords {
+        rules.push("A question mark next to a number prevents chording around it.");
+    } else {
+        rules.push("Question marks are ignored when chording and stay closed.");
+    }
+
+    let bindings = [
+        (Action::Reveal, "Reveal the cell under the keyboard cursor"),
+        (Action::Flag, "Flag the cell under the keyboard cursor"),
+        (
+            Action::Chord,
+            "Reveal around the number under the keyboard cursor",
+        ),
+        (
+            Action::OpenRest,
+            "Open every unflagged cell once all mines are flagged",
+        ),
+        (Action::Hint, "Highlight a cell that is provably safe"),
+        (Action::Probabilities, "Show or hide mine probabilities"),
+        (Action::Restart, "Start a new game"),
+        (Action::Menu, "Return to the menu"),
+    ]
+    .map(|(action, description)| (settings.keys.label(action), description));
+
+    let controls = controls
+        .into_iter()
+        .map(|(input, action)| (input.to_owned(), action))
+        .chain(bindings);
+
+    let controls = Column::with_children(controls.map(|(input, action)| {
+        row![text(input).size(14).width(140), text(action).size(14)]
+            .spacing(8)
+            .into()
+    }))
+    .spacing(4);
+
+    let theme = settings.appearance.theme();
+    let legend = Column::with_children(game_state::legend(&theme).into_iter().map(
+        |(color, glyph_color, glyph, meaning)| {
+            row![swatch(color, glyph_color, glyph), text(meaning).size(14)]
+                .spacing(8)
+                .align_y(Center)
+                .into()
+        },
+    ))
+    .spacing(4);
+
+    let rules =
+        Column::with_children(rules.into_iter().map(|rule| text(rule).size(14).into())).spacing(4);
+
+    scrollable(
+        column![
+            text("Controls").size(18),
+            controls,
+            text("Cells").size(18),
+            legend,
+            text("Rules").size(18),
+            rules,
+            button(text("Back").center().width(Fill))
+                .on_press(on_close)
+                .width(Fill),
+        ]
+        .padding(24)
+        .spacing(12),
+    )
+    .into()
+}
+
+fn swatch<'a, Message: 'a>(
+    color: Color,
+    glyph_color: Color,
+    glyph: &'a str,
+) -> Element<'a, Message> {
+    container(text(glyph).size(16).color(glyph_color))
+        .center(24)
+        .style(move |_| container::Style::default().background(color))
+        .into()
+}
+
+pub fn about<'a, Message: Clone + 'a>(on_close: Message) -> Element<'a, Message> {
+    container(
+        column![
+            text("Minesweeper").size(24),
+            text!("Version {}", env!("CARGO_PKG_VERSION")).size(14),
+            text("Clear the board without setting off a mine.").size(14),
+            button(text("Back").center().width(Fill))
+                .on_press(on_close)
+                .width(Fill),
+        ]
+        .spacing(12)
+        .align_x(Center),
+    )
+    .padding(24)
+    .center(Fill)
+    .into()
+}
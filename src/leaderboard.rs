@@ -0,0 +1,94 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use iced::{
+    Element,
+    widget::{column, text},
+};
+use minesweeper::difficulty::Difficulty;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const FILE: &str = "leaderboard.json";
+const MAX_RECORDS: usize = 5;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Leaderboard {
+    records: BTreeMap<Difficulty, Vec<Record>>,
+    /// Times for games won without placing a flag, ranked apart from the
+    /// others.
+    #[serde(default)]
+    no_flags: BTreeMap<Difficulty, Vec<Record>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Record {
+    pub name: String,
+    pub time: Duration,
+}
+
+impl Leaderboard {
+    pub fn load() -> Self {
+        storage::load(FILE)
+    }
+
+    pub fn records(&self, difficulty: Difficulty, no_flags: bool) -> &[Record] {
+        let table = if no_flags {
+            &self.no_flags
+        } else {
+            &self.records
+        };
+
+        table.get(&difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn qualifies(&self, difficulty: Difficulty, no_flags: bool, time: Duration) -> bool {
+        let records = self.records(difficulty, no_flags);
+        records.len() < MAX_RECORDS || records.iter().any(|record| time < record.time)
+    }
+
+    pub fn insert(&mut self, difficulty: Difficulty, no_flags: bool, name: String, time: Duration) {
+        let table = if no_flags {
+            &mut self.no_flags
+        } else {
+            &mut self.records
+        };
+        let records = table.entry(difficulty).or_default();
+        let index = records.partition_point(|record| record.time <= time);
+        records.insert(index, Record { name, time });
+        records.truncate(MAX_RECORDS);
+
+        storage::save(FILE, self);
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.no_flags.clear();
+        storage::save(FILE, self);
+    }
+
+    pub fn view<'a, Message: 'a>(
+        &'a self,
+        difficulty: Difficulty,
+        no_flags: bool,
+    ) -> Element<'a, Message> {
+        let records = self.records(difficulty, no_flags);
+
+        if records.is_empty() {
+            return text("No records yet").size(14).into();
+        }
+
+        column(records.iter().enumerate().map(|(i, record)| {
+            text!(
+                "{}. {} - {:.1} s",
+                i + 1,
+                record.name,
+                record.time.as_secs_f32()
+            )
+            .size(14)
+            .into()
+        }))
+        .spacing(2)
+        .into()
+    }
+}
@@ -0,0 +1,8 @@
+//! The rules of minesweeper without any user interface: board generation,
+//! revealing, flagging and chording. The game itself is built on top of this.
+
+pub mod board;
+pub mod difficulty;
+pub mod grid;
+pub mod mask;
+pub mod seed;
@@ -0,0 +1,60 @@
+use std::sync::OnceLock;
+
+use tracing::level_filters::LevelFilter;
+#[cfg(not(target_arch = "wasm32"))]
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{Registry, reload};
+#[cfg(not(target_arch = "wasm32"))]
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::settings::LogLevel;
+
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_LOG_FILES: usize = 7;
+
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Starts writing log events to daily rotated files in the platform's local
+/// data directory. The returned guard flushes pending events when dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init(level: LogLevel) -> Option<WorkerGuard> {
+    let directory = dirs::data_local_dir()?.join("minesweeper").join("logs");
+    let appender = rolling::Builder::new()
+        .rotation(rolling::Rotation::DAILY)
+        .filename_prefix("minesweeper")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(directory)
+        .ok()?;
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let (filter, handle) = reload::Layer::new(LevelFilter::from(level));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(writer).with_ansi(false))
+        .try_init()
+        .ok()?;
+
+    LEVEL.set(handle).ok()?;
+
+    Some(guard)
+}
+
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL.get() {
+        let _ = handle.reload(LevelFilter::from(level));
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
@@ -1,105 +1,1298 @@
+use std::time::Duration;
+
+use autosave::Autosave;
+use daily::Day;
 use game_state::GameState;
 use iced::{
+    Alignment::Center,
     Element,
     Length::Fill,
-    Size, Task,
-    widget::{button, column, text},
-    window::{self, Settings},
+    Size, Subscription, Task, clipboard,
+    keyboard::{self, Key, key::Named},
+    time,
+    widget::{
+        button, checkbox, column, container, horizontal_space, pick_list, row, scrollable, text,
+        text_input, tooltip,
+    },
+    window,
+};
+use leaderboard::Leaderboard;
+use menu_bar::Menu;
+use metrics::Metrics;
+use minesweeper::{
+    board::GameStatus,
+    difficulty::Difficulty,
+    grid::{FirstClick, Grid, Neighbourhood, Shape},
+    mask::Outline,
+    seed::Seed,
 };
+use puzzle::{PUZZLES, Progress};
+use replay::{Replay, ReplayViewer};
+use settings::{Action, Settings};
+use skins::Skin;
+use stats::Statistics;
+use time_attack::TimeAttack;
+use toast::Toasts;
+use tracing::{info, warn};
+use web_time::Instant;
 
+mod analysis;
+mod animation;
+mod autosave;
+mod config;
+mod daily;
+mod debug_overlay;
 mod game_state;
+mod help;
+mod leaderboard;
+mod logging;
+mod menu_bar;
+mod metrics;
+mod modal;
+mod patterns;
+mod puzzle;
+mod replay;
+mod settings;
+mod skins;
+mod smiley;
+mod solver;
+#[cfg(feature = "sound")]
+mod sound;
+mod sprites;
+mod stats;
+mod storage;
+mod theme;
+mod time_attack;
+mod timer;
+mod toast;
+mod topology;
+#[cfg(feature = "update-check")]
+mod update_check;
+
+const MENU_SIZE: Size = Size::new(300.0, 830.0 + menu_bar::HEIGHT);
+const MIN_SIZE: Size = Size::new(300.0, 300.0);
+const TOOLBAR_HEIGHT: f32 = 36.0;
+const MAX_WINDOW_SIZE: Size = Size::new(1280.0, 960.0);
 
 fn main() -> iced::Result {
+    let settings = config::load();
+    // The browser has no file system to log to.
+    #[cfg(not(target_arch = "wasm32"))]
+    let _log_guard = logging::init(settings.log_level);
+
     iced::application("Minesweeper", Application::update, Application::view)
-        .window(Settings {
-            resizable: false,
-            size: Size::new(300.0, 300.0),
+        .subscription(Application::subscription)
+        .window(window::Settings {
+            resizable: true,
+            size: MENU_SIZE,
+            min_size: Some(MIN_SIZE),
+            exit_on_close_request: false,
             ..Default::default()
         })
-        .run()
+        .run_with(move || Application::new(settings))
 }
 
+#[derive(Default)]
 enum ApplicationState {
+    #[default]
     Menu,
     Game(GameState),
-}
-
-impl Default for ApplicationState {
-    fn default() -> Self {
-        Self::Menu
-    }
+    Settings,
+    Statistics,
+    Puzzles,
+    Replay(ReplayViewer),
+    Won {
+        elapsed: Duration,
+        metrics: Metrics,
+        no_flags: bool,
+        record_name: Option<String>,
+    },
+    TimeAttackOver(time_attack::Score),
 }
 
 #[derive(Default)]
 struct Application {
     state: ApplicationState,
+    difficulty: Difficulty,
+    /// The board options chosen in the menu. Its size is set by the difficulty.
+    grid: Grid,
+    outline: Outline,
+    seed_input: String,
+    settings: Settings,
+    /// The installed skins, looked up when the settings are opened.
+    skins: Vec<Skin>,
+    leaderboard: Leaderboard,
+    statistics: Statistics,
+    last_replay: Option<Replay>,
+    autosave: Option<Autosave>,
+    time_attack: Option<TimeAttack>,
+    /// The day whose daily board is being played.
+    daily: Option<Day>,
+    /// The number of the puzzle being played.
+    puzzle: Option<usize>,
+    puzzles: Progress,
+    /// The scale factor of the monitor the last game opened on.
+    scale_factor: f32,
+    open_menu: Option<Menu>,
+    toasts: Toasts,
+    /// An action that would abandon the game in progress, held back until
+    /// the player confirms it.
+    pending: Option<Box<Message>>,
+    /// Set once the player agreed to abandon the current game.
+    abandon_confirmed: bool,
+    show_help: bool,
+    show_about: bool,
+    #[cfg(feature = "update-check")]
+    available_update: Option<update_check::Release>,
 }
 
 #[derive(Clone, Debug)]
 enum Message {
     SelectDifficulty(Difficulty),
-    StartGame(GameState),
-    GameMessage(game_state::Message),
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Difficulty {
-    Easy,
-    Medium,
-    Hard,
+    SelectShape(Shape),
+    ToggleWrap(bool),
+    SelectMinesPerCell(u8),
+    SelectFirstClick(FirstClick),
+    SelectNeighbourhood(Neighbourhood),
+    ToggleLiar(bool),
+    SelectOutline(Outline),
+    StartGame(Box<GameState>),
+    StartTimeAttack,
+    StartDaily,
+    ShowPuzzles,
+    StartPuzzle(usize),
+    TimeAttackTick(Instant),
+    ToastTick(Instant),
+    ResumeGame,
+    CloseRequested,
+    Game(game_state::Message),
+    Settings(settings::Message),
+    SeedInput(String),
+    CopySeed(Seed),
+    NewGame,
+    ShowMenu,
+    RecordName(String),
+    SaveRecord,
+    ClearRecords,
+    ShowSettings,
+    ShowStatistics,
+    WatchReplay,
+    Replay(replay::Message),
+    ToggleHelp,
+    ToggleAbout,
+    /// Opens one of the menus in the menu bar, or closes them all.
+    OpenMenu(Option<Menu>),
+    /// An entry picked from a menu, which closes it.
+    Choose(Box<Message>),
+    /// Starts the current board over from the beginning.
+    RestartBoard,
+    /// Deals a fresh board of the same size at once, without asking.
+    QuickRestart,
+    ConfirmAbandon,
+    CancelAbandon,
+    KeyPressed(Key),
+    #[cfg(feature = "update-check")]
+    UpdateChecked(Option<update_check::Release>),
+    #[cfg(feature = "update-check")]
+    DismissUpdate,
 }
 
 impl Application {
+    fn new(settings: Settings) -> (Self, Task<Message>) {
+        let application = Self {
+            settings,
+            leaderboard: Leaderboard::load(),
+            statistics: Statistics::load(),
+            puzzles: Progress::load(),
+            last_replay: Replay::load(),
+            autosave: Autosave::load(),
+            scale_factor: 1.0,
+            ..Self::default()
+        };
+
+        #[cfg(feature = "update-check")]
+        let task = application.check_for_updates();
+        #[cfg(not(feature = "update-check"))]
+        let task = Task::none();
+
+        (application, task)
+    }
+
+    #[cfg(feature = "update-check")]
+    fn check_for_updates(&self) -> Task<Message> {
+        if self.settings.check_for_updates {
+            Task::perform(update_check::latest_release(), Message::UpdateChecked)
+        } else {
+            Task::none()
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        if self.needs_confirmation(&message) {
+            self.pending = Some(Box::new(message));
+            return Task::none();
+        }
+
         match message {
             Message::SelectDifficulty(difficulty) => {
-                let (width, height, mines) = match difficulty {
-                    Difficulty::Easy => (10, 8, 10),
-                    Difficulty::Medium => (18, 14, 40),
-                    Difficulty::Hard => (24, 20, 99),
+                self.abandon_game();
+                self.time_attack = None;
+                self.daily = None;
+                self.puzzle = None;
+
+                let seed = self.entered_seed().unwrap_or_else(Seed::random);
+                self.seed_input.clear();
+
+                info!(?difficulty, %seed, "starting game");
+                self.difficulty = difficulty;
+
+                self.open_game(self.new_game(difficulty, seed))
+            }
+            Message::StartTimeAttack => {
+                self.abandon_game();
+
+                self.puzzle = None;
+
+                info!("starting time attack");
+                self.time_attack = Some(TimeAttack::new(Instant::now()));
+                self.difficulty = Difficulty::Easy;
+
+                self.open_game(self.new_game(Difficulty::Easy, Seed::random()))
+            }
+            Message::StartDaily => {
+                let day = Day::today();
+                if self.statistics.daily().has_attempted(day) {
+                    return Task::none();
+                }
+
+                self.abandon_game();
+                self.time_attack = None;
+                self.puzzle = None;
+
+                info!(%day, "starting daily board");
+                self.statistics.record_daily_attempt(day);
+                self.daily = Some(day);
+                self.difficulty = Difficulty::Medium;
+
+                // Every player gets the classic board, whatever the menu is set to.
+                let (width, height, mines) = Difficulty::Medium.board();
+                self.open_game(GameState::new(
+                    Grid::new(width, height),
+                    mines,
+                    day.seed(),
+                    None,
+                    self.settings.clone(),
+                ))
+            }
+            Message::ShowPuzzles => {
+                self.leave_game();
+                self.state = ApplicationState::Puzzles;
+
+                window::get_oldest().and_then(|id| window::resize(id, MENU_SIZE))
+            }
+            Message::StartPuzzle(index) => {
+                let Some(puzzle) = PUZZLES.get(index) else {
+                    return Task::none();
                 };
 
-                window::get_oldest().and_then(move |id| {
-                    let size = Size::new((width * 32) as f32, (height * 32) as f32);
-                    window::resize(id, size).chain(Task::done(Message::StartGame(GameState::new(
-                        width, height, mines,
-                    ))))
-                })
+                self.abandon_game();
+                self.time_attack = None;
+                self.daily = None;
+
+                info!(puzzle = index + 1, name = %puzzle.name, "starting puzzle");
+                self.puzzle = Some(index);
+
+                self.open_game(
+                    GameState::new(
+                        puzzle.grid(),
+                        0,
+                        Seed::random(),
+                        None,
+                        self.settings.clone(),
+                    )
+                    .with_layout(puzzle.layout()),
+                )
+            }
+            Message::TimeAttackTick(now) => {
+                if let Some(time_attack) = &mut self.time_attack {
+                    time_attack.tick(now);
+
+                    if time_attack.is_over() {
+                        // The board in play counts the cells cleared so far.
+                        if let ApplicationState::Game(state) = &self.state {
+                            time_attack.finish_board(false, state.board().safe_cells_revealed());
+                        }
+
+                        let score = time_attack.score();
+                        info!(?score, "time attack finished");
+
+                        self.statistics.record_time_attack(score);
+                        self.time_attack = None;
+                        self.state = ApplicationState::TimeAttackOver(score);
+                    }
+                }
+
+                Task::none()
             }
-            Message::GameMessage(message) => {
+            Message::ToastTick(now) => {
+                self.toasts.tick(now);
+                Task::none()
+            }
+            Message::Game(game_state::Message::Restart) => self.update(Message::NewGame),
+            Message::Game(message) => {
                 if let ApplicationState::Game(state) = &mut self.state {
-                    state.update(message)
+                    let was_in_progress = state.status() == GameStatus::InProgress;
+                    state.update(message);
+
+                    if was_in_progress && state.status() != GameStatus::InProgress {
+                        state.replay().save();
+                        self.last_replay = Some(state.replay().clone());
+                    }
+
+                    if let Some(time_attack) = &mut self.time_attack {
+                        time_attack.set_paused(state.is_paused(), Instant::now());
+
+                        if was_in_progress && state.status() != GameStatus::InProgress {
+                            if state.status() == GameStatus::Won {
+                                self.toasts.push("Board cleared");
+                            }
+
+                            time_attack.finish_board(
+                                state.status() == GameStatus::Won,
+                                state.board().safe_cells_revealed(),
+                            );
+                            self.state = ApplicationState::Game(
+                                self.new_game(self.difficulty, Seed::random())
+                                    .with_restart(false),
+                            );
+                        }
+
+                        return Task::none();
+                    }
+
+                    // Puzzles stay out of the statistics and records, and
+                    // the board stays up once solved.
+                    if let Some(index) = self.puzzle {
+                        if was_in_progress && state.status() == GameStatus::Won {
+                            info!(puzzle = index + 1, "puzzle solved");
+                            self.puzzles.mark_solved(index);
+                            self.toasts.push(format!("Puzzle {} solved", index + 1));
+                        }
+
+                        return Task::none();
+                    }
+
+                    if was_in_progress && state.status() == GameStatus::Lost {
+                        match self.daily.take() {
+                            Some(day) => self.statistics.record_daily(day, false),
+                            // Variant boards are not comparable with the
+                            // classic ones, so they stay out of the statistics.
+                            None if !state.is_classic() => {}
+                            None => self.statistics.record_loss(
+                                self.difficulty,
+                                state.is_no_flags(),
+                                state.metrics(),
+                            ),
+                        }
+                    }
+
+                    if was_in_progress && state.status() == GameStatus::Won {
+                        let elapsed = state.elapsed();
+                        let best = self
+                            .leaderboard
+                            .records(self.difficulty, state.is_no_flags())
+                            .first();
+
+                        if state.undos_used() == 0
+                            && state.hints_used() == 0
+                            && !state.was_insured()
+                            && state.is_classic()
+                            && best.is_none_or(|best| elapsed < best.time)
+                        {
+                            self.toasts.push(format!(
+                                "New personal best: {:.1} s!",
+                                elapsed.as_secs_f32()
+                            ));
+                        }
+
+                        match self.daily.take() {
+                            Some(day) => self.statistics.record_daily(day, true),
+                            None if !state.is_classic() => {}
+                            None => self.statistics.record_win(
+                                self.difficulty,
+                                state.is_no_flags(),
+                                state.metrics(),
+                            ),
+                        }
+                    }
+
+                    // The board stays up until the confetti has fallen.
+                    if state.status() == GameStatus::Won && !state.is_animating() {
+                        let elapsed = state.elapsed();
+
+                        self.state = ApplicationState::Won {
+                            elapsed,
+                            metrics: state.metrics(),
+                            no_flags: state.is_no_flags(),
+                            record_name: (state.undos_used() == 0
+                                && state.hints_used() == 0
+                                && !state.was_insured()
+                                && state.is_classic()
+                                && self.leaderboard.qualifies(
+                                    self.difficulty,
+                                    state.is_no_flags(),
+                                    elapsed,
+                                ))
+                            .then(String::new),
+                        };
+                    }
                 }
 
                 Task::none()
             }
+            Message::ResumeGame => {
+                let Some(autosave) = self.autosave.take() else {
+                    return Task::none();
+                };
+                Autosave::remove();
+
+                let Some(game_state) = GameState::resume(autosave.game) else {
+                    warn!("autosave does not describe a valid board");
+                    return Task::none();
+                };
+
+                info!(difficulty = ?autosave.difficulty, "resuming game");
+                self.difficulty = autosave.difficulty;
+                self.time_attack = None;
+                self.daily = None;
+                self.puzzle = None;
+
+                self.open_game(game_state)
+            }
+            Message::CloseRequested => {
+                if let ApplicationState::Game(state) = &self.state
+                    && self.time_attack.is_none()
+                    && self.daily.is_none()
+                    && self.puzzle.is_none()
+                    && state.has_started()
+                    && state.status() == GameStatus::InProgress
+                {
+                    info!("saving unfinished game");
+                    Autosave::new(self.difficulty, state.save()).save();
+                }
+
+                iced::exit()
+            }
             Message::StartGame(game_state) => {
+                self.scale_factor = game_state.scale_factor();
+                self.abandon_confirmed = false;
+                // Time attack and daily boards cannot be played again.
+                let restartable = self.time_attack.is_none() && self.daily.is_none();
+                self.state = ApplicationState::Game(game_state.with_restart(restartable));
+                Task::none()
+            }
+            Message::Settings(message) => {
+                self.settings.update(message.clone());
+
+                if let ApplicationState::Game(state) = &mut self.state {
+                    state.set_settings(self.settings.clone());
+                }
+
+                if let settings::Message::LogLevel(level) = message {
+                    logging::set_level(level);
+                }
+
+                #[cfg(feature = "update-check")]
+                if let settings::Message::CheckForUpdates(true) = message {
+                    return self.check_for_updates();
+                }
+
+                Task::none()
+            }
+            Message::SelectShape(shape) => {
+                self.grid.shape = shape;
+                Task::none()
+            }
+            Message::ToggleWrap(wrap) => {
+                self.grid.wrap = wrap;
+                Task::none()
+            }
+            Message::SelectMinesPerCell(mines) => {
+                self.grid.mines_per_cell = mines;
+                Task::none()
+            }
+            Message::SelectFirstClick(first_click) => {
+                self.grid.first_click = first_click;
+                Task::none()
+            }
+            Message::SelectNeighbourhood(neighbourhood) => {
+                self.grid.neighbourhood = neighbourhood;
+                Task::none()
+            }
+            Message::ToggleLiar(liar) => {
+                self.grid.liar = liar;
+                Task::none()
+            }
+            Message::SelectOutline(outline) => {
+                self.outline = outline;
+                Task::none()
+            }
+            Message::SeedInput(input) => {
+                self.seed_input = input;
+                Task::none()
+            }
+            Message::CopySeed(seed) => {
+                self.toasts.push("Seed copied");
+                clipboard::write(seed.to_string())
+            }
+            Message::NewGame if self.time_attack.is_some() => self.update(Message::StartTimeAttack),
+            Message::NewGame => match self.puzzle {
+                Some(index) => self.update(Message::StartPuzzle(index)),
+                None => self.update(Message::SelectDifficulty(self.difficulty)),
+            },
+            Message::ShowMenu => {
+                info!("returning to menu");
+                self.leave_game();
+                self.state = ApplicationState::Menu;
+
+                window::get_oldest().and_then(|id| window::resize(id, MENU_SIZE))
+            }
+            Message::RecordName(name) => {
+                if let ApplicationState::Won {
+                    record_name: Some(record_name),
+                    ..
+                } = &mut self.state
+                {
+                    *record_name = name;
+                }
+
+                Task::none()
+            }
+            Message::SaveRecord => {
+                if let ApplicationState::Won {
+                    elapsed,
+                    no_flags,
+                    record_name,
+                    ..
+                } = &mut self.state
+                    && let Some(name) = record_name.take()
+                {
+                    let name = match name.trim() {
+                        "" => "Anonymous".to_owned(),
+                        name => name.to_owned(),
+                    };
+
+                    info!(difficulty = ?self.difficulty, no_flags, %name, ?elapsed, "new record");
+                    self.leaderboard
+                        .insert(self.difficulty, *no_flags, name, *elapsed);
+                }
+
+                Task::none()
+            }
+            Message::ClearRecords => {
+                info!("clearing records");
+                self.leaderboard.clear();
+                Task::none()
+            }
+            Message::ShowSettings => {
+                self.leave_game();
+                self.skins = skins::available();
+                self.state = ApplicationState::Settings;
+
+                window::get_oldest().and_then(|id| window::resize(id, MENU_SIZE))
+            }
+            Message::ShowStatistics => {
+                self.leave_game();
+                self.state = ApplicationState::Statistics;
+
+                window::get_oldest().and_then(|id| window::resize(id, MENU_SIZE))
+            }
+            Message::WatchReplay => {
+                let Some(replay) = self.last_replay.clone() else {
+                    return Task::none();
+                };
+
+                let viewer = ReplayViewer::new(replay);
+                let size = viewer.size();
+                self.state = ApplicationState::Replay(viewer);
+
+                window::get_oldest().and_then(move |id| window::resize(id, size))
+            }
+            Message::Replay(replay::Message::Close) => self.update(Message::ShowMenu),
+            Message::Replay(message) => {
+                if let ApplicationState::Replay(viewer) = &mut self.state {
+                    viewer.update(message);
+                }
+
+                Task::none()
+            }
+            Message::KeyPressed(key) => {
+                if self.settings.is_rebinding() {
+                    return self.update(Message::Settings(settings::Message::KeyPressed(key)));
+                }
+
+                if key == Key::Named(Named::F1) {
+                    return self.update(Message::ToggleHelp);
+                }
+
+                if key == Key::Named(Named::F2) {
+                    return self.update(Message::QuickRestart);
+                }
+
+                match (self.settings.keys.action(&key), &self.state) {
+                    (
+                        Some(Action::Restart),
+                        ApplicationState::Game(_) | ApplicationState::Won { .. },
+                    ) => self.update(Message::NewGame),
+                    (Some(Action::Menu), _) if self.show_help => self.update(Message::ToggleHelp),
+                    (Some(Action::Menu), state) if !matches!(state, ApplicationState::Menu) => {
+                        self.update(Message::ShowMenu)
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+                Task::none()
+            }
+            Message::ToggleAbout => {
+                self.show_about = !self.show_about;
+                Task::none()
+            }
+            Message::QuickRestart => {
+                let game_state = match &self.state {
+                    _ if self.time_attack.is_some() => return Task::none(),
+                    ApplicationState::Game(state) => {
+                        let game_state = GameState::new(
+                            state.grid(),
+                            state.mines(),
+                            Seed::random(),
+                            state.mask().cloned(),
+                            self.settings.clone(),
+                        )
+                        .with_scale_factor(self.scale_factor);
+
+                        match state.fixed_layout() {
+                            Some(layout) => game_state.with_layout(layout.clone()),
+                            None => game_state,
+                        }
+                    }
+                    ApplicationState::Won { .. } => self.new_game(self.difficulty, Seed::random()),
+                    _ => return Task::none(),
+                };
+
+                self.abandon_game();
+                self.daily = None;
+                self.pending = None;
+                self.abandon_confirmed = false;
+                info!(seed = %game_state.seed(), "quick restart");
+
+                // The board keeps its size, so the window does too.
                 self.state = ApplicationState::Game(game_state);
                 Task::none()
             }
+            Message::ConfirmAbandon => match self.pending.take() {
+                Some(message) => {
+                    self.abandon_confirmed = true;
+                    self.update(*message)
+                }
+                None => Task::none(),
+            },
+            Message::CancelAbandon => {
+                self.pending = None;
+                Task::none()
+            }
+            Message::OpenMenu(menu) => {
+                self.open_menu = menu;
+                Task::none()
+            }
+            Message::Choose(message) => {
+                self.open_menu = None;
+                self.update(*message)
+            }
+            Message::RestartBoard => {
+                let ApplicationState::Game(state) = &self.state else {
+                    return Task::none();
+                };
+                let game_state = GameState::new(
+                    state.grid(),
+                    state.mines(),
+                    state.seed(),
+                    state.mask().cloned(),
+                    self.settings.clone(),
+                );
+                let game_state = match state.fixed_layout() {
+                    Some(layout) => game_state.with_layout(layout.clone()),
+                    None => game_state,
+                };
+
+                self.abandon_game();
+                info!(seed = %game_state.seed(), "restarting board");
+
+                self.open_game(game_state)
+            }
+            #[cfg(feature = "update-check")]
+            Message::UpdateChecked(release) => {
+                self.available_update = release;
+                Task::none()
+            }
+            #[cfg(feature = "update-check")]
+            Message::DismissUpdate => {
+                self.available_update = None;
+                Task::none()
+            }
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn subscription(&self) -> Subscription<Message> {
+        let global = Subscription::batch([
+            keyboard::on_key_press(|key, _| Some(Message::KeyPressed(key))),
+            window::close_requests().map(|_| Message::CloseRequested),
+            self.toasts.subscription().map(Message::ToastTick),
+        ]);
+
+        let time_attack = match self.time_attack {
+            Some(_) => {
+                time::every(Duration::from_secs(1)).map(|_| Message::TimeAttackTick(Instant::now()))
+            }
+            None => Subscription::none(),
+        };
+
         match &self.state {
-            ApplicationState::Menu => column![
-                button(text("Easy").center().width(Fill))
-                    .on_press(Message::SelectDifficulty(Difficulty::Easy))
-                    .width(Fill),
-                button(text("Medium").center().width(Fill))
-                    .on_press(Message::SelectDifficulty(Difficulty::Medium))
-                    .width(Fill),
-                button(text("Hard").center().width(Fill))
-                    .on_press(Message::SelectDifficulty(Difficulty::Hard))
-                    .width(Fill),
+            ApplicationState::Game(game_state) if !self.show_help => Subscription::batch([
+                global,
+                time_attack,
+                game_state.subscription().map(Message::Game),
+            ]),
+            ApplicationState::Game(_) => Subscription::batch([global, time_attack]),
+            ApplicationState::Replay(viewer) if !self.show_help => {
+                Subscription::batch([global, viewer.subscription().map(Message::Replay)])
+            }
+            _ => global,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let confirmation = self.pending.as_ref().map(|_| {
+            column![
+                text("Abandon this game?").size(18),
+                text("Your progress on this board will be lost.").size(14),
+                row![
+                    button(text("Keep playing").size(14)).on_press(Message::CancelAbandon),
+                    button(text("Abandon").size(14))
+                        .on_press(Message::ConfirmAbandon)
+                        .style(button::danger),
+                ]
+                .spacing(8),
             ]
-            .padding(24)
             .spacing(12)
-            .width(Fill)
+            .into()
+        });
+        let screen = self.toasts.view(modal::view(
+            self.screen(),
+            confirmation,
+            Message::CancelAbandon,
+        ));
+
+        menu_bar::view(screen, self.open_menu, Message::OpenMenu, |menu| {
+            self.menu_items(menu)
+        })
+    }
+
+    fn menu_items(&self, menu: Menu) -> Vec<Element<'_, Message>> {
+        let choose = |message| Some(Message::Choose(Box::new(message)));
+
+        match menu {
+            Menu::Game => {
+                // Time attack and daily boards cannot be played again.
+                let can_restart = matches!(self.state, ApplicationState::Game(_))
+                    && self.time_attack.is_none()
+                    && self.daily.is_none();
+
+                [
+                    menu_bar::item("New", choose(Message::NewGame)),
+                    menu_bar::item(
+                        "Restart",
+                        can_restart.then(|| choose(Message::RestartBoard)).flatten(),
+                    ),
+                    menu_bar::heading("Difficulty"),
+                ]
+                .into_iter()
+                .chain(Difficulty::ALL.map(|difficulty| {
+                    menu_bar::item(
+                        format!("  {difficulty:?}"),
+                        self.start_game(difficulty).and_then(choose),
+                    )
+                }))
+                .chain([
+                    menu_bar::item("Puzzles", choose(Message::ShowPuzzles)),
+                    menu_bar::item("Statistics", choose(Message::ShowStatistics)),
+                    menu_bar::item("Back to menu", choose(Message::ShowMenu)),
+                ])
+                .collect()
+            }
+            Menu::Options => {
+                let toggle = |label, value, message: fn(bool) -> settings::Message| {
+                    checkbox(label, value)
+                        .on_toggle(move |value| Message::Settings(message(value)))
+                        .size(14)
+                        .text_size(13)
+                        .into()
+                };
+
+                vec![
+                    toggle(
+                        "Question marks",
+                        self.settings.marks.question_marks,
+                        settings::Message::QuestionMarks,
+                    ),
+                    toggle(
+                        "Lock flags",
+                        self.settings.input.flag_lock,
+                        settings::Message::FlagLock,
+                    ),
+                    toggle(
+                        "No flags (NF, next game)",
+                        self.settings.input.no_flags,
+                        settings::Message::NoFlags,
+                    ),
+                    toggle(
+                        "Flag obvious mines",
+                        self.settings.assists.auto_flag,
+                        settings::Message::AutoFlag,
+                    ),
+                    toggle(
+                        "Allow undo",
+                        self.settings.assists.undo,
+                        settings::Message::Undo,
+                    ),
+                    toggle(
+                        "Shapes and patterns",
+                        self.settings.accessibility.patterns,
+                        settings::Message::Patterns,
+                    ),
+                    menu_bar::item("All settings", choose(Message::ShowSettings)),
+                ]
+            }
+            Menu::Help => vec![
+                menu_bar::item("Controls and rules (F1)", choose(Message::ToggleHelp)),
+                menu_bar::item("About", choose(Message::ToggleAbout)),
+            ],
+        }
+    }
+
+    fn screen(&self) -> Element<'_, Message> {
+        if self.show_about {
+            return help::about(Message::ToggleAbout);
+        }
+
+        if self.show_help {
+            return help::view(&self.settings, Message::ToggleHelp);
+        }
+
+        match &self.state {
+            ApplicationState::Menu => column![]
+                .push_maybe(self.update_banner())
+                .push_maybe(self.autosave.as_ref().map(|autosave| {
+                    button(
+                        text!("Resume {:?} game", autosave.difficulty)
+                            .center()
+                            .width(Fill),
+                    )
+                    .on_press(Message::ResumeGame)
+                    .width(Fill)
+                }))
+                .push(
+                    column![
+                        pick_list(Shape::ALL, Some(self.grid.shape), Message::SelectShape)
+                            .width(Fill),
+                        pick_list(Outline::ALL, Some(self.outline), Message::SelectOutline)
+                            .width(Fill),
+                        pick_list(
+                            Neighbourhood::ALL,
+                            Some(self.grid.neighbourhood),
+                            Message::SelectNeighbourhood
+                        )
+                        .width(Fill),
+                        checkbox("Numbers lie by one", self.grid.liar)
+                            .on_toggle(Message::ToggleLiar),
+                        checkbox("Wrap around edges", self.grid.wrap)
+                            .on_toggle(Message::ToggleWrap),
+                        row![
+                            text("Mines per cell"),
+                            horizontal_space(),
+                            pick_list(
+                                (1..=Grid::MAX_MINES_PER_CELL).collect::<Vec<_>>(),
+                                Some(self.grid.mines_per_cell),
+                                Message::SelectMinesPerCell
+                            ),
+                        ]
+                        .align_y(Center),
+                        pick_list(
+                            FirstClick::ALL,
+                            Some(self.grid.first_click),
+                            Message::SelectFirstClick
+                        )
+                        .width(Fill),
+                        button(text("Easy").center().width(Fill))
+                            .on_press_maybe(self.start_game(Difficulty::Easy))
+                            .width(Fill),
+                        button(text("Medium").center().width(Fill))
+                            .on_press_maybe(self.start_game(Difficulty::Medium))
+                            .width(Fill),
+                        button(text("Hard").center().width(Fill))
+                            .on_press_maybe(self.start_game(Difficulty::Hard))
+                            .width(Fill),
+                        button(text("Huge").center().width(Fill))
+                            .on_press_maybe(self.start_game(Difficulty::Huge))
+                            .width(Fill),
+                        button(
+                            text!("Time attack ({} min)", time_attack::BUDGET.as_secs() / 60)
+                                .center()
+                                .width(Fill)
+                        )
+                        .on_press(Message::StartTimeAttack)
+                        .width(Fill),
+                        row![
+                            self.daily_button(),
+                            button(text("Puzzles").center().width(Fill))
+                                .on_press(Message::ShowPuzzles)
+                                .width(Fill),
+                        ]
+                        .spacing(12),
+                        text_input("Seed (optional)", &self.seed_input)
+                            .on_input(Message::SeedInput),
+                        button(text("Settings").center().width(Fill))
+                            .on_press(Message::ShowSettings)
+                            .width(Fill),
+                        button(text("Help (F1)").center().width(Fill))
+                            .on_press(Message::ToggleHelp)
+                            .width(Fill),
+                        button(text("Statistics").center().width(Fill))
+                            .on_press(Message::ShowStatistics)
+                            .width(Fill),
+                        button(text("Watch last game").center().width(Fill))
+                            .on_press_maybe(
+                                self.last_replay.is_some().then_some(Message::WatchReplay)
+                            )
+                            .width(Fill),
+                        self.best_times(),
+                    ]
+                    .spacing(12),
+                )
+                .padding(24)
+                .spacing(12)
+                .width(Fill)
+                .into(),
+            ApplicationState::Game(game_state) => column![
+                self.toolbar(game_state),
+                game_state.view().map(Message::Game)
+            ]
+            .into(),
+            ApplicationState::Settings => scrollable(
+                column![
+                    self.settings.view(&self.skins).map(Message::Settings),
+                    button(text("Back").center().width(Fill))
+                        .on_press(Message::ShowMenu)
+                        .width(Fill),
+                ]
+                .padding(24)
+                .spacing(12),
+            )
             .into(),
-            ApplicationState::Game(game_state) => game_state
-                .view()
-                .map(|message| Message::GameMessage(message)),
+            ApplicationState::Statistics => self.statistics.view(Message::ShowMenu),
+            ApplicationState::Puzzles => self.puzzles.view(Message::StartPuzzle, Message::ShowMenu),
+            ApplicationState::Replay(viewer) => viewer.view().map(Message::Replay),
+            ApplicationState::Won {
+                elapsed,
+                metrics,
+                no_flags,
+                record_name,
+            } => container(scrollable(
+                column![
+                    text("You won!").size(32),
+                    text!("Time: {:.1} s", elapsed.as_secs_f32()),
+                    text!(
+                        "3BV: {}  3BV/s: {:.2}  Efficiency: {:.0}%",
+                        metrics.three_bv,
+                        metrics.per_second(),
+                        metrics.efficiency()
+                    )
+                    .size(14),
+                    text!("Clicks: {} ({})", metrics.clicks.total(), metrics.clicks).size(14),
+                ]
+                .push_maybe(record_name.as_ref().map(|name| {
+                    row![
+                        text_input("New record! Your name", name)
+                            .on_input(Message::RecordName)
+                            .on_submit(Message::SaveRecord),
+                        button(text("Save")).on_press(Message::SaveRecord),
+                    ]
+                    .spacing(8)
+                }))
+                .push(self.leaderboard.view(self.difficulty, *no_flags))
+                .push(
+                    button(text("Play again").center().width(Fill))
+                        .on_press(Message::NewGame)
+                        .width(Fill),
+                )
+                .push(
+                    button(text("Menu").center().width(Fill))
+                        .on_press(Message::ShowMenu)
+                        .width(Fill),
+                )
+                .padding(24)
+                .spacing(12)
+                .align_x(Center),
+            ))
+            .center(Fill)
+            .into(),
+            ApplicationState::TimeAttackOver(score) => {
+                let best = self.statistics.time_attack().best;
+
+                container(
+                    column![
+                        text("Time's up!").size(32),
+                        text!("Boards cleared: {}", score.boards),
+                        text!("Cells cleared: {}", score.cells),
+                        text!("Best: {} boards, {} cells", best.boards, best.cells).size(14),
+                        button(text("Play again").center().width(Fill))
+                            .on_press(Message::StartTimeAttack)
+                            .width(Fill),
+                        button(text("Menu").center().width(Fill))
+                            .on_press(Message::ShowMenu)
+                            .width(Fill),
+                    ]
+                    .padding(24)
+                    .spacing(12)
+                    .align_x(Center),
+                )
+                .center(Fill)
+                .into()
+            }
+        }
+    }
+
+    fn new_game(&self, difficulty: Difficulty, seed: Seed) -> GameState {
+        let (width, height, mines) = difficulty.board();
+        let grid = Grid {
+            width,
+            height,
+            ..self.grid
+        };
+        let mask = self.outline.mask(width, height);
+        // Shaped boards keep the difficulty's density of mines.
+        let mines = mask
+            .as_ref()
+            .map_or(mines, |mask| mines * mask.cell_count() / (width * height));
+
+        GameState::new(grid, mines, seed, mask, self.settings.clone())
+            .with_scale_factor(self.scale_factor)
+    }
+
+    /// Fits the window to the board, as far as it fits on screen, and then
+    /// shows it. Larger boards are zoomed and panned instead. The monitor's
+    /// scale factor is read first, since cells are sized in whole physical
+    /// pixels.
+    fn open_game(&self, game_state: GameState) -> Task<Message> {
+        window::get_oldest().and_then(move |id| {
+            let game_state = game_state.clone();
+
+            window::get_scale_factor(id).then(move |scale_factor| {
+                let game_state = game_state.clone().with_scale_factor(scale_factor);
+                let size = (game_state.size() + Size::new(0.0, TOOLBAR_HEIGHT + menu_bar::HEIGHT))
+                    .min(MAX_WINDOW_SIZE);
+
+                window::resize(id, size).chain(Task::done(Message::StartGame(Box::new(game_state))))
+            })
+        })
+    }
+
+    fn best_times(&self) -> Element<'_, Message> {
+        let best = Difficulty::ALL.map(|difficulty| {
+            let no_flags = self
+                .leaderboard
+                .records(difficulty, true)
+                .first()
+                .map(|record| format!("  (NF {:.1} s)", record.time.as_secs_f32()))
+                .unwrap_or_default();
+
+            match self.leaderboard.records(difficulty, false).first() {
+                Some(record) => text!(
+                    "{difficulty:?}: {:.1} s by {}{no_flags}",
+                    record.time.as_secs_f32(),
+                    record.name
+                ),
+                None => text!("{difficulty:?}: -{no_flags}"),
+            }
+            .size(14)
+            .into()
+        });
+
+        column![
+            row![
+                text("Best times"),
+                horizontal_space(),
+                button(text("Clear").size(12)).on_press(Message::ClearRecords),
+            ]
+            .align_y(Center),
+        ]
+        .extend(best)
+        .spacing(4)
+        .into()
+    }
+
+    /// Whether the message would end a game the player has put work into,
+    /// or a daily board, which cannot be tried again.
+    fn needs_confirmation(&self, message: &Message) -> bool {
+        let abandons = matches!(
+            message,
+            Message::SelectDifficulty(_)
+                | Message::NewGame
+                | Message::ShowMenu
+                | Message::ShowPuzzles
+                | Message::ShowSettings
+                | Message::ShowStatistics
+                | Message::StartTimeAttack
+                | Message::StartDaily
+                | Message::StartPuzzle(_)
+                | Message::RestartBoard
+        );
+
+        abandons
+            && !self.abandon_confirmed
+            && matches!(
+                &self.state,
+                ApplicationState::Game(state)
+                    if state.status() == GameStatus::InProgress
+                        && (state.has_started() || self.daily.is_some())
+            )
+    }
+
+    fn abandon_game(&mut self) {
+        if let ApplicationState::Game(state) = &self.state
+            && self.time_attack.is_none()
+            && self.puzzle.is_none()
+            && state.status() == GameStatus::InProgress
+        {
+            // The daily attempt is used up as soon as the board is opened.
+            if let Some(day) = self.daily.take() {
+                info!(%day, "daily board abandoned");
+                self.statistics.record_daily(day, false);
+            } else if state.has_started() && state.is_classic() {
+                info!(difficulty = ?self.difficulty, "game abandoned");
+                self.statistics
+                    .record_abandoned(self.difficulty, state.is_no_flags());
+            }
         }
     }
+
+    /// Abandons the game on screen, if any, and ends the mode it was
+    /// played in.
+    fn leave_game(&mut self) {
+        self.abandon_game();
+        self.time_attack = None;
+        self.daily = None;
+        self.puzzle = None;
+    }
+
+    fn daily_button(&self) -> Element<'_, Message> {
+        let day = Day::today();
+        let (label, message) = if self.statistics.daily().has_attempted(day) {
+            ("Daily (played today)", None)
+        } else {
+            ("Daily", Some(Message::StartDaily))
+        };
+
+        button(text(label).center().width(Fill))
+            .on_press_maybe(message)
+            .width(Fill)
+            .into()
+    }
+
+    fn entered_seed(&self) -> Option<Seed> {
+        self.seed_input.parse().ok()
+    }
+
+    fn start_game(&self, difficulty: Difficulty) -> Option<Message> {
+        (self.seed_input.trim().is_empty() || self.entered_seed().is_some())
+            .then_some(Message::SelectDifficulty(difficulty))
+    }
+
+    fn toolbar(&self, game_state: &GameState) -> Element<'_, Message> {
+        let seed = game_state.seed();
+        let undo = self.settings.assists.undo.then(|| {
+            row![
+                button(text("Undo").size(12)).on_press_maybe(
+                    game_state
+                        .can_undo()
+                        .then_some(Message::Game(game_state::Message::Undo))
+                ),
+                button(text("Redo").size(12)).on_press_maybe(
+                    game_state
+                        .can_redo()
+                        .then_some(Message::Game(game_state::Message::Redo))
+                ),
+            ]
+            .spacing(4)
+        });
+
+        let time_attack = self.time_attack.map(|time_attack| {
+            text!(
+                "{} s left, {} boards",
+                time_attack.remaining().as_secs(),
+                time_attack.score().boards
+            )
+            .size(12)
+        });
+
+        let daily = self.daily.map(|day| text!("Daily {day}").size(12));
+        let puzzle = self
+            .puzzle
+            .map(|index| text!("Puzzle {}", index + 1).size(12));
+
+        row![
+            button(text("New game").size(12)).on_press(Message::NewGame),
+            button(text("Menu").size(12)).on_press(Message::ShowMenu),
+            button(text("Hint").size(12)).on_press_maybe(
+                (game_state.status() == GameStatus::InProgress)
+                    .then_some(Message::Game(game_state::Message::Hint))
+            ),
+        ]
+        .push_maybe(undo)
+        .push_maybe(time_attack)
+        .push_maybe(daily)
+        .push_maybe(puzzle)
+        .push(horizontal_space())
+        .push(tooltip(
+            button(text(seed.to_string()).size(12)).on_press(Message::CopySeed(seed)),
+            text("Copy seed").size(12),
+            tooltip::Position::Bottom,
+        ))
+        .spacing(8)
+        .align_y(Center)
+        .padding(4)
+        .height(TOOLBAR_HEIGHT)
+        .into()
+    }
+
+    #[cfg(feature = "update-check")]
+    fn update_banner(&self) -> Option<Element<'_, Message>> {
+        let release = self.available_update.as_ref()?;
+
+        Some(
+            column![
+                text!("Version {} is available", release.version),
+                scrollable(text(&release.notes).size(12)).height(80),
+                button(text("Dismiss").size(14)).on_press(Message::DismissUpdate),
+            ]
+            .spacing(4)
+            .into(),
+        )
+    }
+
+    #[cfg(not(feature = "update-check"))]
+    fn update_banner(&self) -> Option<Element<'_, Message>> {
+        None
+    }
 }
@@ -0,0 +1,172 @@
+//! Boards in other shapes than a rectangle, cut out of their grid by a mask.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Position;
+
+/// Which cells of a grid are part of the board. Cells outside the mask are
+/// never drawn, hold no mines and do not count as anyone's neighbour.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Mask {
+    width: usize,
+    cells: Vec<bool>,
+}
+
+impl Mask {
+    /// Builds a mask by asking `contains` about every cell.
+    pub fn from_fn(width: usize, height: usize, contains: impl Fn(Position) -> bool) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |column| Position::new(row, column)))
+            .map(contains)
+            .collect();
+
+        Self { width, cells }
+    }
+
+    /// Reads a bitmap drawn row by row, where `#` marks a cell on the board
+    /// and anything else a gap. Shorter rows are padded with gaps.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        Self::from_fn(width, rows.len(), |position| {
+            rows[position.row() as usize]
+                .chars()
+                .nth(position.column() as usize)
+                == Some('#')
+        })
+    }
+
+    /// Whether the cell is part of the board. Cells beyond the mask's own
+    /// size are not.
+    pub fn contains(&self, position: Position) -> bool {
+        let (Ok(row), Ok(column)) = (
+            usize::try_from(position.row()),
+            usize::try_from(position.column()),
+        ) else {
+            return false;
+        };
+
+        column < self.width
+            && self
+                .cells
+                .get(row * self.width + column)
+                .copied()
+                .unwrap_or(false)
+    }
+
+    /// The number of cells on the board.
+    pub fn cell_count(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell).count()
+    }
+}
+
+/// The shapes offered for new boards, which fit themselves to the board's
+/// size.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Outline {
+    #[default]
+    Rectangle,
+    Diamond,
+    Heart,
+    Donut,
+}
+
+impl Outline {
+    pub const ALL: [Outline; 4] = [
+        Outline::Rectangle,
+        Outline::Diamond,
+        Outline::Heart,
+        Outline::Donut,
+    ];
+
+    /// The mask for a board of the given size, or `None` for the plain
+    /// rectangle.
+    pub fn mask(self, width: usize, height: usize) -> Option<Mask> {
+        // Cell centres, scaled so the board spans -1 to 1 both ways.
+        let scaled = |position: Position| {
+            let x = (position.column() as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+            let y = (position.row() as f32 + 0.5) / height as f32 * 2.0 - 1.0;
+            (x, y)
+        };
+
+        let contains: fn(f32, f32) -> bool = match self {
+            Outline::Rectangle => return None,
+            Outline::Diamond => |x, y| x.abs() + y.abs() <= 1.05,
+            Outline::Heart => |x, y| {
+                // The heart curve, turned upright and fitted to the board.
+                let (x, y) = (x * 1.2, 0.1 - y * 1.15);
+                (x * x + y * y - 1.0).powi(3) - x * x * y.powi(3) <= 0.0
+            },
+            Outline::Donut => |x, y| (0.16..=1.05).contains(&(x * x + y * y)),
+        };
+
+        Some(Mask::from_fn(width, height, |position| {
+            let (x, y) = scaled(position);
+            contains(x, y)
+        }))
+    }
+}
+
+impl fmt::Display for Outline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Outline::Rectangle => "Rectangular board",
+            Outline::Diamond => "Diamond",
+            Outline::Heart => "Heart",
+            Outline::Donut => "Donut",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_rows_reads_the_drawing() {
+        let mask = Mask::from_rows(&[".#.", "###", "#"]);
+
+        assert_eq!(mask.cell_count(), 5);
+        assert!(mask.contains(Position::new(0, 1)));
+        assert!(!mask.contains(Position::new(0, 0)));
+        // Short rows are padded with gaps, and nothing lies beyond the mask.
+        assert!(!mask.contains(Position::new(2, 1)));
+        assert!(!mask.contains(Position::new(1, 3)));
+        assert!(!mask.contains(Position::new(0, 0).offset(-1, 0)));
+    }
+
+    #[test]
+    fn outlines_fit_the_board() {
+        let (width, height) = (16, 16);
+        let middle = Position::new(height / 2, width / 2);
+        let corner = Position::new(0, 0);
+
+        assert!(Outline::Rectangle.mask(width, height).is_none());
+
+        for outline in [Outline::Diamond, Outline::Heart, Outline::Donut] {
+            let mask = outline.mask(width, height).unwrap();
+
+            assert!(
+                mask.cell_count() > width * height / 3,
+                "{outline} is too small"
+            );
+            assert!(!mask.contains(corner), "{outline} reaches the corner");
+        }
+
+        assert!(
+            Outline::Diamond
+                .mask(width, height)
+                .unwrap()
+                .contains(middle)
+        );
+        assert!(!Outline::Donut.mask(width, height).unwrap().contains(middle));
+    }
+}
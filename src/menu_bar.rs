@@ -0,0 +1,114 @@
+//! The row of drop-down menus at the top of the window.
+
+use std::fmt;
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    widget::{Space, button, column, container, mouse_area, opaque, row, stack, text},
+};
+
+pub const HEIGHT: f32 = 28.0;
+const TITLE_WIDTH: f32 = 70.0;
+const DROPDOWN_WIDTH: f32 = 160.0;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Menu {
+    Game,
+    Options,
+    Help,
+}
+
+impl Menu {
+    const ALL: [Menu; 3] = [Menu::Game, Menu::Options, Menu::Help];
+}
+
+impl fmt::Display for Menu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Menu::Game => "Game",
+            Menu::Options => "Options",
+            Menu::Help => "Help",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Puts the menu bar above `content`. The open menu drops down over the
+/// content, and clicking anywhere else closes it.
+pub fn view<'a, Message: Clone + 'a>(
+    content: Element<'a, Message>,
+    open: Option<Menu>,
+    on_open: impl Fn(Option<Menu>) -> Message,
+    items: impl FnOnce(Menu) -> Vec<Element<'a, Message>>,
+) -> Element<'a, Message> {
+    let titles = Menu::ALL.map(|menu| {
+        let toggled = (open != Some(menu)).then_some(menu);
+
+        button(text(menu.to_string()).size(13).center().width(Fill))
+            .on_press(on_open(toggled))
+            .style(if open == Some(menu) {
+                button::secondary
+            } else {
+                button::text
+            })
+            .padding(4)
+            .width(TITLE_WIDTH)
+            .into()
+    });
+
+    let page = column![
+        container(row(titles).align_y(Center))
+            .style(container::rounded_box)
+            .height(HEIGHT)
+            .width(Fill),
+        content,
+    ];
+
+    // The page stays in a stack while menus open and close, so that it keeps
+    // the state of its widgets.
+    stack![page]
+        .push_maybe(open.map(|menu| {
+            let index = Menu::ALL
+                .iter()
+                .position(|&m| m == menu)
+                .unwrap_or_default();
+            let dropdown = opaque(
+                container(column(items(menu)).spacing(2))
+                    .style(container::bordered_box)
+                    .padding(4)
+                    .width(DROPDOWN_WIDTH),
+            );
+
+            mouse_area(
+                column![
+                    Space::with_height(HEIGHT),
+                    row![Space::with_width(index as f32 * TITLE_WIDTH), dropdown],
+                ]
+                .width(Fill)
+                .height(Fill),
+            )
+            .on_press(on_open(None))
+        }))
+        .into()
+}
+
+/// An entry in a menu, greyed out without a message.
+pub fn item<'a, Message: Clone + 'a>(
+    label: impl text::IntoFragment<'a>,
+    on_press: Option<Message>,
+) -> Element<'a, Message> {
+    button(text(label).size(13))
+        .on_press_maybe(on_press)
+        .style(button::text)
+        .padding([2, 8])
+        .width(Fill)
+        .into()
+}
+
+/// A heading for the entries below it.
+pub fn heading<'a, Message: 'a>(label: &'a str) -> Element<'a, Message> {
+    text(label).size(11).into()
+}
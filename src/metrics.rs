@@ -0,0 +1,154 @@
+use std::{collections::HashSet, fmt, time::Duration};
+
+use minesweeper::board::{Board, Cell, CellType, Position};
+use serde::{Deserialize, Serialize};
+
+/// How a game was played compared to the fewest clicks its board allows.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct Metrics {
+    pub three_bv: usize,
+    pub solved_three_bv: usize,
+    pub clicks: Clicks,
+    pub elapsed: Duration,
+}
+
+/// Clicks on the board during a game, by what they did.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct Clicks {
+    pub left: usize,
+    pub right: usize,
+    pub chord: usize,
+}
+
+impl Clicks {
+    pub fn total(&self) -> usize {
+        self.left + self.right + self.chord
+    }
+}
+
+impl std::ops::AddAssign for Clicks {
+    fn add_assign(&mut self, other: Self) {
+        self.left += other.left;
+        self.right += other.right;
+        self.chord += other.chord;
+    }
+}
+
+impl fmt::Display for Clicks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} left, {} right, {} chord",
+            self.left, self.right, self.chord
+        )
+    }
+}
+
+impl Metrics {
+    pub fn per_second(&self) -> f32 {
+        match self.elapsed.as_secs_f32() {
+            0.0 => 0.0,
+            seconds => self.solved_three_bv as f32 / seconds,
+        }
+    }
+
+    pub fn efficiency(&self) -> f32 {
+        match self.clicks.total() {
+            0 => 0.0,
+            clicks => self.solved_three_bv as f32 / clicks as f32 * 100.0,
+        }
+    }
+}
+
+/// The board's 3BV: the minimum number of left clicks needed to clear it,
+/// counting one per opening and one per number not bordering an opening.
+pub fn three_bv(board: &Board) -> usize {
+    count(board, |_| true)
+}
+
+/// The part of the board's 3BV that has been cleared: openings with at least
+/// one revealed cell and revealed numbers outside any opening.
+pub fn solved_three_bv(board: &Board) -> usize {
+    count(board, |cell| cell.is_revealed())
+}
+
+fn count(board: &Board, solved: impl Fn(Cell) -> bool) -> usize {
+    let grid = board.grid();
+    // Liar boards never open more than the cell clicked.
+    let is_empty = |position: &Position| {
+        !grid.liar
+            && board
+                .get(*position)
+                .is_some_and(|cell| matches!(cell.cell_type(), CellType::NonMine { neighbours: 0 }))
+    };
+
+    let mut seen = HashSet::new();
+    let mut total = 0;
+
+    for (position, _) in board.cells().filter(|(p, _)| is_empty(p)) {
+        if !seen.insert(position) {
+            continue;
+        }
+
+        let mut opening = vec![position];
+        let mut is_solved = false;
+
+        while let Some(current) = opening.pop() {
+            is_solved |= board.get(current).is_some_and(&solved);
+
+            for n in grid.neighbours(current) {
+                if is_empty(&n) && seen.insert(n) {
+                    opening.push(n);
+                }
+            }
+        }
+
+        total += usize::from(is_solved);
+    }
+
+    total
+        + board
+            .cells()
+            .filter(|(position, cell)| {
+                !cell.is_mine()
+                    && !is_empty(position)
+                    && !grid.neighbours(*position).iter().any(is_empty)
+                    && solved(*cell)
+            })
+            .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use minesweeper::{grid::Grid, seed::Seed};
+
+    use super::*;
+
+    fn row(width: usize, mines: &[usize]) -> Board {
+        let mines: Vec<_> = mines
+            .iter()
+            .map(|&column| Position::new(0, column))
+            .collect();
+        Board::with_mines(Grid::new(width, 1), Seed::from_day(0), &mines)
+    }
+
+    #[test]
+    fn three_bv_counts_openings_and_lone_numbers() {
+        // Two openings, each taking the number beside the mine with it.
+        assert_eq!(three_bv(&row(7, &[3])), 2);
+        // 1 * 2 * 1: three numbers with no opening between them.
+        assert_eq!(three_bv(&row(5, &[1, 3])), 3);
+    }
+
+    #[test]
+    fn solved_three_bv_counts_what_is_open() {
+        let mut board = row(7, &[3]);
+        assert_eq!(solved_three_bv(&board), 0);
+
+        board.reveal(Position::new(0, 0));
+        assert_eq!(solved_three_bv(&board), 1);
+
+        board.reveal(Position::new(0, 6));
+        assert_eq!(solved_three_bv(&board), 2);
+    }
+}
@@ -0,0 +1,41 @@
+//! A dialog shown over a dimmed page, which takes all input until it closes.
+
+use iced::{
+    Color, Element, Theme,
+    widget::{center, container, mouse_area, opaque, stack},
+};
+
+/// Shows `dialog`, if there is one, centred over `base`. Clicking beside the
+/// dialog sends `on_blur`.
+///
+/// The page sits in the same place either way, so it keeps the state of its
+/// widgets while dialogs come and go.
+pub fn view<'a, Message: Clone + 'a>(
+    base: Element<'a, Message>,
+    dialog: Option<Element<'a, Message>>,
+    on_blur: Message,
+) -> Element<'a, Message> {
+    stack![base]
+        .push_maybe(dialog.map(|dialog| {
+            opaque(
+                mouse_area(
+                    center(opaque(
+                        container(dialog)
+                            .style(container::bordered_box)
+                            .padding(16)
+                            .max_width(320),
+                    ))
+                    .style(backdrop),
+                )
+                .on_press(on_blur),
+            )
+        }))
+        .into()
+}
+
+fn backdrop(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.6).into()),
+        ..container::Style::default()
+    }
+}
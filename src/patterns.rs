@@ -0,0 +1,95 @@
+//! Shapes that tell cells apart without relying on colour, drawn on top of
+//! the tiles when the accessibility setting is on.
+
+use iced::{
+    Point, Vector,
+    widget::canvas::{Frame, Stroke, Text, path},
+};
+use minesweeper::board::{Cell, CellType, Marking, Position};
+
+use crate::{theme::BoardTheme, topology::Topology};
+
+const CHIP_RADIUS: f32 = 0.36;
+/// Stripes stay within this distance of the centre, so that they fit inside
+/// cells of any shape.
+const STRIPE_RADIUS: f32 = 0.45;
+const STRIPE_SPACING: f32 = 0.15;
+
+/// Whether the pattern stands in for the cell's glyph.
+pub fn replaces_glyph(cell: Cell) -> bool {
+    !cell.is_revealed() && cell.marking() == Marking::Flag
+}
+
+/// Numbers sit on chips in their colour, mines are striped and flags are
+/// drawn as pennants, with a count when a cell holds several.
+pub fn draw(
+    frame: &mut Frame,
+    topology: &dyn Topology,
+    cells: impl Iterator<Item = (Position, Cell)>,
+    theme: &BoardTheme,
+) {
+    let mut chips: [path::Builder; 8] = std::array::from_fn(|_| path::Builder::new());
+    let mut stripes = path::Builder::new();
+    let mut pennants = path::Builder::new();
+    let mut poles = path::Builder::new();
+
+    for (position, cell) in cells {
+        let centre = topology.centre(position);
+
+        match cell.cell_type() {
+            CellType::NonMine { .. } if cell.is_revealed() => {
+                if let Some(number @ 1..) = cell.number() {
+                    chips[number.min(chips.len()) - 1].circle(centre, CHIP_RADIUS);
+                }
+            }
+            CellType::Mine { .. } if cell.is_revealed() => stripe(&mut stripes, centre),
+            _ if replaces_glyph(cell) => {
+                let pole = centre + Vector::new(-0.15, 0.0);
+
+                poles.move_to(pole + Vector::new(0.0, -0.3));
+                poles.line_to(pole + Vector::new(0.0, 0.3));
+                pennants.move_to(pole + Vector::new(0.0, -0.3));
+                pennants.line_to(pole + Vector::new(0.4, -0.15));
+                pennants.line_to(pole);
+                pennants.close();
+
+                if cell.flags() > 1 {
+                    frame.fill_text(Text {
+                        content: cell.flags().to_string(),
+                        position: centre + Vector::new(0.3, 0.3),
+                        size: 0.3.into(),
+                        color: theme.glyph,
+                        horizontal_alignment: iced::alignment::Horizontal::Center,
+                        vertical_alignment: iced::alignment::Vertical::Center,
+                        ..Default::default()
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (neighbours, chips) in chips.into_iter().enumerate() {
+        frame.fill(&chips.build(), theme.numbers.color(neighbours + 1));
+    }
+
+    let stroke = Stroke::default().with_width(0.05).with_color(theme.glyph);
+    frame.stroke(&stripes.build(), stroke);
+    frame.stroke(&poles.build(), stroke.with_width(0.06));
+    frame.fill(&pennants.build(), theme.glyph);
+}
+
+/// Diagonal lines across a circle around the centre.
+fn stripe(builder: &mut path::Builder, centre: Point) {
+    let along = Vector::new(1.0, -1.0) * std::f32::consts::FRAC_1_SQRT_2;
+    let across = Vector::new(1.0, 1.0) * std::f32::consts::FRAC_1_SQRT_2;
+
+    for i in -2..=2 {
+        let offset = i as f32 * STRIPE_SPACING;
+        let half_length = (STRIPE_RADIUS.powi(2) - offset.powi(2)).sqrt();
+        let middle = centre + across * offset;
+
+        builder.move_to(middle + along * -half_length);
+        builder.line_to(middle + along * half_length);
+    }
+}
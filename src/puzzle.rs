@@ -0,0 +1,149 @@
+//! Hand-made boards with their mines in fixed places and the first cell
+//! already open, each solvable without guessing.
+
+use std::{collections::BTreeSet, sync::LazyLock};
+
+use iced::{
+    Element,
+    Length::Fill,
+    widget::{button, column, scrollable, text},
+};
+use minesweeper::{board::Position, grid::Grid};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::storage;
+
+const FILE: &str = "puzzles.json";
+
+const SOURCES: [&str; 8] = [
+    include_str!("../assets/puzzles/01.json"),
+    include_str!("../assets/puzzles/02.json"),
+    include_str!("../assets/puzzles/03.json"),
+    include_str!("../assets/puzzles/04.json"),
+    include_str!("../assets/puzzles/05.json"),
+    include_str!("../assets/puzzles/06.json"),
+    include_str!("../assets/puzzles/07.json"),
+    include_str!("../assets/puzzles/08.json"),
+];
+
+/// The puzzles in the order they are offered. Puzzles that fail to parse
+/// are left out.
+pub static PUZZLES: LazyLock<Vec<Puzzle>> = LazyLock::new(|| {
+    SOURCES
+        .iter()
+        .filter_map(|source| {
+            serde_json::from_str(source)
+                .inspect_err(|error| warn!(%error, "could not parse puzzle"))
+                .ok()
+        })
+        .collect()
+});
+
+/// A puzzle as it is written: the board drawn row by row, with `*` for a
+/// mine and `.` for a safe cell, and the cell that starts open.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Puzzle {
+    pub name: String,
+    start: (usize, usize),
+    board: Vec<String>,
+}
+
+/// Where a fixed board's mines are and which cell it starts with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Layout {
+    pub mines: Vec<Position>,
+    pub start: Position,
+}
+
+impl Puzzle {
+    pub fn grid(&self) -> Grid {
+        let width = self.board.iter().map(String::len).max().unwrap_or(0);
+        Grid::new(width, self.board.len())
+    }
+
+    pub fn layout(&self) -> Layout {
+        let mines = self
+            .board
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells
+                    .char_indices()
+                    .filter(|&(_, cell)| cell == '*')
+                    .map(move |(column, _)| Position::new(row, column))
+            })
+            .collect();
+
+        Layout {
+            mines,
+            start: Position::new(self.start.0, self.start.1),
+        }
+    }
+}
+
+/// Which puzzles have been solved, by their place in the list.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Progress {
+    solved: BTreeSet<usize>,
+}
+
+impl Progress {
+    pub fn load() -> Self {
+        storage::load(FILE)
+    }
+
+    pub fn mark_solved(&mut self, index: usize) {
+        if self.solved.insert(index) {
+            storage::save(FILE, self);
+        }
+    }
+
+    /// Lists the puzzles by number, marking the solved ones.
+    pub fn view<'a, Message: Clone + 'a>(
+        &self,
+        on_select: impl Fn(usize) -> Message,
+        on_close: Message,
+    ) -> Element<'a, Message> {
+        let puzzles = PUZZLES.iter().enumerate().map(|(index, puzzle)| {
+            let grid = puzzle.grid();
+            let solved = if self.solved.contains(&index) {
+                "  (solved)"
+            } else {
+                ""
+            };
+
+            button(text!(
+                "{}. {} - {}x{}{solved}",
+                index + 1,
+                puzzle.name,
+                grid.width,
+                grid.height
+            ))
+            .on_press(on_select(index))
+            .style(button::secondary)
+            .width(Fill)
+            .into()
+        });
+
+        scrollable(
+            column![
+                text!(
+                    "Puzzles ({} of {} solved)",
+                    self.solved.len(),
+                    PUZZLES.len()
+                )
+                .size(24)
+            ]
+            .extend(puzzles)
+            .push(
+                button(text("Back").center().width(Fill))
+                    .on_press(on_close)
+                    .width(Fill),
+            )
+            .padding(24)
+            .spacing(8),
+        )
+        .into()
+    }
+}
@@ -0,0 +1,252 @@
+use std::{fmt, time::Duration};
+
+use iced::{
+    Alignment::Center,
+    Element, Size, Subscription,
+    widget::{button, column, horizontal_space, pick_list, row, text},
+    window,
+};
+use minesweeper::{grid::Grid, mask::Mask, seed::Seed};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::{
+    game_state::{self, GameState},
+    puzzle::Layout,
+    settings::Settings,
+    storage,
+};
+
+const FILE: &str = "replay.json";
+const CONTROLS_HEIGHT: f32 = 36.0;
+
+/// Everything needed to play a game back: the board parameters and every
+/// message the game received, timed from the first one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Replay {
+    #[serde(flatten)]
+    grid: Grid,
+    mines: usize,
+    seed: Seed,
+    #[serde(default)]
+    mask: Option<Mask>,
+    /// The fixed mines and starting cell of a puzzle.
+    #[serde(default)]
+    layout: Option<Layout>,
+    /// Boxed, as every game carries a replay and the settings are large.
+    settings: Box<Settings>,
+    moves: Vec<(Duration, game_state::Message)>,
+    #[serde(skip)]
+    started_at: Option<Instant>,
+}
+
+impl Replay {
+    pub fn new(
+        grid: Grid,
+        mines: usize,
+        seed: Seed,
+        mask: Option<Mask>,
+        settings: Settings,
+    ) -> Self {
+        Self {
+            grid,
+            mines,
+            seed,
+            mask,
+            layout: None,
+            settings: Box::new(settings),
+            moves: Vec::new(),
+            started_at: None,
+        }
+    }
+
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_ref()
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+    }
+
+    pub fn load() -> Option<Self> {
+        storage::load(FILE)
+    }
+
+    pub fn save(&self) {
+        storage::save(FILE, self);
+    }
+
+    pub fn record(&mut self, message: game_state::Message, now: Instant) {
+        let started_at = *self.started_at.get_or_insert(now);
+        self.moves.push((now - started_at, message));
+    }
+
+    /// Continues recording after a restart of the application, placing new
+    /// moves right after the last recorded one.
+    pub fn resume(&mut self, now: Instant) {
+        let last = self.moves.last().map_or(Duration::ZERO, |&(at, _)| at);
+        self.started_at = now.checked_sub(last);
+    }
+
+    /// Leaves time in which the game could not be played, such as a pause,
+    /// out of the recording.
+    pub fn skip(&mut self, duration: Duration) {
+        if let Some(started_at) = &mut self.started_at {
+            *started_at += duration;
+        }
+    }
+
+    fn game(&self) -> GameState {
+        let game = GameState::new(
+            self.grid,
+            self.mines,
+            self.seed,
+            self.mask.clone(),
+            (*self.settings).clone(),
+        );
+
+        match &self.layout {
+            Some(layout) => game.with_layout(layout.clone()),
+            None => game,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Speed {
+    Half,
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+}
+
+impl Speed {
+    const ALL: [Speed; 4] = [Speed::Half, Speed::Normal, Speed::Double, Speed::Quadruple];
+
+    fn factor(self) -> f32 {
+        match self {
+            Speed::Half => 0.5,
+            Speed::Normal => 1.0,
+            Speed::Double => 2.0,
+            Speed::Quadruple => 4.0,
+        }
+    }
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x", self.factor())
+    }
+}
+
+/// Plays a [`Replay`] back on a board of its own, which ignores clicks.
+#[derive(Clone, Debug)]
+pub struct ReplayViewer {
+    replay: Replay,
+    game: GameState,
+    next_move: usize,
+    position: Duration,
+    speed: Speed,
+    playing: bool,
+    last_frame: Option<Instant>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Game(game_state::Message),
+    TogglePlaying,
+    Rewind,
+    Speed(Speed),
+    Frame(Instant),
+    Close,
+}
+
+impl ReplayViewer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            game: replay.game(),
+            replay,
+            next_move: 0,
+            position: Duration::ZERO,
+            speed: Speed::default(),
+            playing: true,
+            last_frame: None,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.game.size() + Size::new(0.0, CONTROLS_HEIGHT)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.next_move >= self.replay.moves.len()
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Game(message @ game_state::Message::Tick(_)) => self.game.update(message),
+            Message::Game(_) => {}
+            Message::TogglePlaying => {
+                self.playing = !self.playing;
+                self.last_frame = None;
+            }
+            Message::Rewind => *self = Self::new(self.replay.clone()),
+            Message::Speed(speed) => self.speed = speed,
+            Message::Frame(now) => {
+                if let Some(last_frame) = self.last_frame {
+                    self.position += (now - last_frame).mul_f32(self.speed.factor());
+                }
+                self.last_frame = Some(now);
+
+                while let Some(&(at, message)) = self.replay.moves.get(self.next_move)
+                    && at <= self.position
+                {
+                    self.game.update(message);
+                    self.next_move += 1;
+                }
+
+                self.game.update(game_state::Message::Tick(now));
+
+                if self.is_finished() {
+                    self.playing = false;
+                }
+            }
+            Message::Close => {}
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.playing {
+            window::frames().map(Message::Frame)
+        } else {
+            self.game.subscription().map(Message::Game)
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let controls = row![
+            button(text(if self.playing { "Pause" } else { "Play" }).size(14)).on_press_maybe(
+                (self.playing || !self.is_finished()).then_some(Message::TogglePlaying)
+            ),
+            button(text("Rewind").size(14)).on_press(Message::Rewind),
+            pick_list(Speed::ALL, Some(self.speed), Message::Speed).text_size(14),
+            horizontal_space(),
+            button(text("Back").size(14)).on_press(Message::Close),
+        ]
+        .spacing(8)
+        .padding(4)
+        .height(CONTROLS_HEIGHT)
+        .align_y(Center);
+
+        column![controls, self.game.view().map(Message::Game)].into()
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fmt, str::FromStr};
+
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+/// Determines the mine layout of a board, shared as a base36 code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Seed(u64);
+
+impl Seed {
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// The seed of the daily board, scrambled so that consecutive days do not
+    /// get similar codes.
+    pub fn from_day(day: u64) -> Self {
+        Self(day.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(29))
+    }
+
+    pub fn rng(self) -> StdRng {
+        StdRng::seed_from_u64(self.0)
+    }
+
+    /// A coin toss decided by the seed and `salt`, for choices that have to
+    /// come out the same whatever order they are made in.
+    pub fn toss(self, salt: u64) -> bool {
+        // The finaliser of SplitMix64.
+        let mut z = self.0 ^ salt.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        (z ^ (z >> 31)) & 1 == 1
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = Vec::new();
+        let mut value = self.0;
+
+        loop {
+            digits.push(char::from_digit((value % 36) as u32, 36).unwrap_or('0'));
+            value /= 36;
+
+            if value == 0 {
+                break;
+            }
+        }
+
+        f.write_str(&digits.iter().rev().collect::<String>())
+    }
+}
+
+impl FromStr for Seed {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s.trim(), 36).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base36_round_trips() {
+        for value in [0, 35, 36, 1_234_567_890, u64::MAX] {
+            let seed = Seed(value);
+            assert_eq!(seed.to_string().parse::<Seed>(), Ok(seed));
+        }
+
+        assert_eq!(Seed(36).to_string(), "10");
+        assert_eq!(" ZZ ".parse::<Seed>(), Ok(Seed(35 * 36 + 35)));
+        assert!("not a seed".parse::<Seed>().is_err());
+    }
+}
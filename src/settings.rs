@@ -0,0 +1,479 @@
+use std::{collections::BTreeMap, fmt};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    keyboard::Key,
+    widget::{button, checkbox, column, horizontal_space, pick_list, row, text},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    skins::Skin,
+    theme::{Appearance, TileStyle},
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    #[cfg(feature = "update-check")]
+    pub check_for_updates: bool,
+    pub input: InputSettings,
+    pub assists: AssistSettings,
+    pub marks: MarkSettings,
+    pub keys: KeyBindings,
+    pub appearance: Appearance,
+    pub tiles: TileStyle,
+    pub cell_size: CellSize,
+    pub skin: Skin,
+    pub accessibility: AccessibilitySettings,
+    #[cfg(feature = "sound")]
+    pub sound: SoundSettings,
+    pub log_level: LogLevel,
+    #[serde(skip)]
+    rebinding: Option<Action>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InputSettings {
+    pub left_click_chords: bool,
+    pub right_click_chords: bool,
+    pub flag_lock: bool,
+    /// Non-flagging play: cells cannot be marked, so there is nothing to
+    /// chord against either.
+    pub no_flags: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AssistSettings {
+    pub auto_finish: bool,
+    pub auto_flag: bool,
+    pub auto_open: bool,
+    /// Forgives the first mine hit by defusing it.
+    pub insurance: bool,
+    pub undo: bool,
+    pub hint_penalty: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Supplements colours with shapes and patterns on the board.
+    pub patterns: bool,
+}
+
+#[cfg(feature = "sound")]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub muted: bool,
+    /// The master volume, from silent at 0 to the samples' own level at 1.
+    pub volume: f32,
+}
+
+#[cfg(feature = "sound")]
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 0.8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MarkSettings {
+    pub question_marks: bool,
+    pub question_marks_block_chords: bool,
+}
+
+impl Default for MarkSettings {
+    fn default() -> Self {
+        Self {
+            question_marks: true,
+            question_marks_block_chords: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub enum Action {
+    Reveal,
+    Flag,
+    Chord,
+    OpenRest,
+    Hint,
+    Probabilities,
+    Restart,
+    Menu,
+}
+
+impl Action {
+    const ALL: [Action; 8] = [
+        Action::Reveal,
+        Action::Flag,
+        Action::Chord,
+        Action::OpenRest,
+        Action::Hint,
+        Action::Probabilities,
+        Action::Restart,
+        Action::Menu,
+    ];
+
+    fn default_key(self) -> &'static str {
+        match self {
+            Action::Reveal => "Space",
+            Action::Flag => "f",
+            Action::Chord => "c",
+            Action::OpenRest => "o",
+            Action::Hint => "h",
+            Action::Probabilities => "p",
+            Action::Restart => "r",
+            Action::Menu => "Escape",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Reveal => "Reveal",
+            Action::Flag => "Flag",
+            Action::Chord => "Chord",
+            Action::OpenRest => "Open the rest",
+            Action::Hint => "Hint",
+            Action::Probabilities => "Probabilities",
+            Action::Restart => "New game",
+            Action::Menu => "Back to menu",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The key bound to each [`Action`]. Keys are stored by name: the lowercase
+/// character for printable keys and the variant name for named keys, such as
+/// `Space` or `F2`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct KeyBindings(BTreeMap<Action, String>);
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> &str {
+        self.0
+            .get(&action)
+            .map_or(action.default_key(), String::as_str)
+    }
+
+    pub fn action(&self, key: &Key) -> Option<Action> {
+        let name = key_name(key)?;
+
+        Action::ALL
+            .into_iter()
+            .find(|&action| self.key(action) == name)
+    }
+
+    /// The bound key as shown to the player.
+    pub fn label(&self, action: Action) -> String {
+        let key = self.key(action);
+
+        if key.chars().count() == 1 {
+            key.to_uppercase()
+        } else {
+            key.to_owned()
+        }
+    }
+}
+
+pub fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(c) => Some(c.to_lowercase()),
+        Key::Named(named) => Some(format!("{named:?}")),
+        Key::Unidentified => None,
+    }
+}
+
+/// The size of a cell at which a new game opens its window. The board still
+/// scales with the window afterwards.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum CellSize {
+    Small,
+    Medium,
+    #[default]
+    Normal,
+    Large,
+}
+
+impl CellSize {
+    const ALL: [CellSize; 4] = [
+        CellSize::Small,
+        CellSize::Medium,
+        CellSize::Normal,
+        CellSize::Large,
+    ];
+
+    pub fn pixels(self) -> f32 {
+        match self {
+            CellSize::Small => 16.0,
+            CellSize::Medium => 24.0,
+            CellSize::Normal => 32.0,
+            CellSize::Large => 48.0,
+        }
+    }
+}
+
+impl fmt::Display for CellSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} px", self.pixels())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Error => "Errors",
+            LogLevel::Warn => "Warnings",
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    LeftClickChords(bool),
+    RightClickChords(bool),
+    FlagLock(bool),
+    NoFlags(bool),
+    AutoFinish(bool),
+    AutoFlag(bool),
+    AutoOpen(bool),
+    Insurance(bool),
+    Undo(bool),
+    HintPenalty(bool),
+    QuestionMarks(bool),
+    QuestionMarksBlockChords(bool),
+    Appearance(Appearance),
+    Tiles(TileStyle),
+    CellSize(CellSize),
+    Skin(Skin),
+    Patterns(bool),
+    LogLevel(LogLevel),
+    Rebind(Action),
+    KeyPressed(Key),
+    #[cfg(feature = "update-check")]
+    CheckForUpdates(bool),
+    #[cfg(feature = "sound")]
+    Muted(bool),
+    #[cfg(feature = "sound")]
+    Volume(f32),
+}
+
+impl Settings {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::LeftClickChords(enabled) => self.input.left_click_chords = enabled,
+            Message::RightClickChords(enabled) => self.input.right_click_chords = enabled,
+            Message::FlagLock(enabled) => self.input.flag_lock = enabled,
+            Message::NoFlags(enabled) => self.input.no_flags = enabled,
+            Message::AutoFinish(enabled) => self.assists.auto_finish = enabled,
+            Message::AutoFlag(enabled) => self.assists.auto_flag = enabled,
+            Message::AutoOpen(enabled) => self.assists.auto_open = enabled,
+            Message::Insurance(enabled) => self.assists.insurance = enabled,
+            Message::Undo(enabled) => self.assists.undo = enabled,
+            Message::HintPenalty(enabled) => self.assists.hint_penalty = enabled,
+            Message::QuestionMarks(enabled) => self.marks.question_marks = enabled,
+            Message::QuestionMarksBlockChords(enabled) => {
+                self.marks.question_marks_block_chords = enabled
+            }
+            Message::Appearance(appearance) => self.appearance = appearance,
+            Message::Tiles(style) => self.tiles = style,
+            Message::CellSize(size) => self.cell_size = size,
+            Message::Skin(skin) => self.skin = skin,
+            Message::Patterns(enabled) => self.accessibility.patterns = enabled,
+            Message::LogLevel(level) => self.log_level = level,
+            Message::Rebind(action) => {
+                self.rebinding = Some(action);
+                return;
+            }
+            Message::KeyPressed(key) => {
+                if let Some(action) = self.rebinding.take()
+                    && let Some(name) = key_name(&key)
+                {
+                    // An action that already had the key takes over the old
+                    // one, so no two actions share a key.
+                    if let Some(other) = self.keys.action(&key)
+                        && other != action
+                    {
+                        let previous = self.keys.key(action).to_owned();
+                        self.keys.0.insert(other, previous);
+                    }
+
+                    self.keys.0.insert(action, name);
+                }
+            }
+            #[cfg(feature = "update-check")]
+            Message::CheckForUpdates(enabled) => self.check_for_updates = enabled,
+            #[cfg(feature = "sound")]
+            Message::Muted(muted) => self.sound.muted = muted,
+            #[cfg(feature = "sound")]
+            Message::Volume(volume) => self.sound.volume = volume,
+        }
+
+        config::save(self);
+    }
+
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding.is_some()
+    }
+
+    /// Shows the settings, offering the installed skins besides the built-in
+    /// one.
+    pub fn view<'a>(&'a self, skins: &[Skin]) -> Element<'a, Message> {
+        let skins: Vec<_> = [Skin::BuiltIn].into_iter().chain(skins.to_vec()).collect();
+
+        let bindings = Action::ALL.map(|action| {
+            let key = if self.rebinding == Some(action) {
+                "Press a key".to_owned()
+            } else {
+                self.keys.label(action)
+            };
+
+            row![
+                text(action.to_string()).size(14),
+                horizontal_space(),
+                button(text(key).size(14).center().width(Fill))
+                    .on_press(Message::Rebind(action))
+                    .width(120),
+            ]
+            .align_y(Center)
+            .into()
+        });
+
+        let view = column![
+            checkbox("Left-click chords on numbers", self.input.left_click_chords)
+                .on_toggle(Message::LeftClickChords),
+            checkbox(
+                "Right-click chords on numbers",
+                self.input.right_click_chords
+            )
+            .on_toggle(Message::RightClickChords),
+            checkbox("Lock flags (Shift or hold to remove)", self.input.flag_lock)
+                .on_toggle(Message::FlagLock),
+            checkbox("Play without flags (NF)", self.input.no_flags).on_toggle(Message::NoFlags),
+            checkbox(
+                "Flag remaining mines when cleared",
+                self.assists.auto_finish
+            )
+            .on_toggle(Message::AutoFinish),
+            checkbox("Flag obvious mines automatically", self.assists.auto_flag)
+                .on_toggle(Message::AutoFlag),
+            checkbox(
+                "Open the rest once every mine is flagged",
+                self.assists.auto_open
+            )
+            .on_toggle(Message::AutoOpen),
+            checkbox("Forgive the first mine hit", self.assists.insurance)
+                .on_toggle(Message::Insurance),
+            checkbox("Allow undo (Ctrl+Z)", self.assists.undo).on_toggle(Message::Undo),
+            checkbox("Hints add 10 seconds", self.assists.hint_penalty)
+                .on_toggle(Message::HintPenalty),
+            checkbox(
+                "Right click cycles through question marks",
+                self.marks.question_marks
+            )
+            .on_toggle(Message::QuestionMarks),
+            checkbox(
+                "Question marks block chords",
+                self.marks.question_marks_block_chords
+            )
+            .on_toggle(Message::QuestionMarksBlockChords),
+            row![
+                text("Theme"),
+                pick_list(Appearance::ALL, Some(self.appearance), Message::Appearance)
+                    .text_size(14),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            row![
+                text("Tiles"),
+                pick_list(TileStyle::ALL, Some(self.tiles), Message::Tiles).text_size(14),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            row![
+                text("Cell size"),
+                pick_list(CellSize::ALL, Some(self.cell_size), Message::CellSize).text_size(14),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            row![
+                text("Skin"),
+                pick_list(skins, Some(&self.skin), |skin| { Message::Skin(skin) }).text_size(14),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            checkbox(
+                "Shapes and patterns alongside colours",
+                self.accessibility.patterns
+            )
+            .on_toggle(Message::Patterns),
+            row![
+                text("Log level"),
+                pick_list(LogLevel::ALL, Some(self.log_level), Message::LogLevel).text_size(14),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            text("Keys"),
+        ]
+        .extend(bindings)
+        .spacing(8);
+
+        #[cfg(feature = "sound")]
+        let view = view
+            .push(checkbox("Mute sounds", self.sound.muted).on_toggle(Message::Muted))
+            .push(
+                row![
+                    text("Volume"),
+                    iced::widget::slider(0.0..=1.0, self.sound.volume, Message::Volume).step(0.05),
+                ]
+                .spacing(8)
+                .align_y(Center),
+            );
+
+        #[cfg(feature = "update-check")]
+        let view = view.push(
+            checkbox("Check for updates", self.check_for_updates)
+                .on_toggle(Message::CheckForUpdates),
+        );
+
+        view.into()
+    }
+}
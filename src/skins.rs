@@ -0,0 +1,144 @@
+//! Tile sets the player puts in the `skins` folder of the config directory,
+//! one folder per skin with a `skin.toml` manifest:
+//!
+//! ```toml
+//! # One image holding every tile. Without it, each tile is a PNG file of its
+//! # own, named after it: covered.png, revealed.png, flag.png,
+//! # question_mark.png, mine.png and 1.png to 8.png.
+//! sheet = "tiles.png"
+//! # The side of a tile in the sheet, in pixels. Defaults to the sheet's height.
+//! tile_size = 16
+//!
+//! # Where tiles sit in the sheet, in tiles from the top left. Tiles left out
+//! # follow the built-in order along the first row.
+//! [tiles]
+//! mine = [5, 0]
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The tiles the board is drawn with in the classic style.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Skin {
+    #[default]
+    BuiltIn,
+    /// A skin folder, by name.
+    Custom(String),
+}
+
+impl fmt::Display for Skin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Skin::BuiltIn => f.write_str("Built-in"),
+            Skin::Custom(name) => f.write_str(name),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod disk {
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use serde::Deserialize;
+    use tracing::{info, warn};
+
+    use super::Skin;
+    use crate::{
+        sprites::{Pixels, Sprites},
+        storage,
+    };
+
+    const MANIFEST: &str = "skin.toml";
+
+    #[derive(Debug, Deserialize)]
+    struct Manifest {
+        sheet: Option<String>,
+        tile_size: Option<u32>,
+        #[serde(default)]
+        tiles: HashMap<String, [u32; 2]>,
+    }
+
+    fn directory() -> Option<PathBuf> {
+        Some(storage::config_dir()?.join("skins"))
+    }
+
+    /// The skins that are installed, by name.
+    pub fn available() -> Vec<Skin> {
+        let Some(entries) = directory().and_then(|directory| fs::read_dir(directory).ok()) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<_> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().join(MANIFEST).is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+
+        names.into_iter().map(Skin::Custom).collect()
+    }
+
+    pub fn load(name: &str) -> Option<Sprites> {
+        let path = directory()?.join(name);
+        let manifest = fs::read_to_string(path.join(MANIFEST))
+            .inspect_err(|error| warn!(name, %error, "could not read skin manifest"))
+            .ok()?;
+        let manifest: Manifest = toml::from_str(&manifest)
+            .inspect_err(|error| warn!(name, %error, "could not parse skin manifest"))
+            .ok()?;
+
+        let read = |file: &str| {
+            fs::read(path.join(file))
+                .inspect_err(|error| warn!(name, file, %error, "could not read skin image"))
+                .ok()
+                .and_then(|png| Pixels::decode(&png))
+        };
+
+        let sprites = match &manifest.sheet {
+            Some(sheet) => {
+                let sheet = read(sheet)?;
+                let size = manifest.tile_size.unwrap_or(sheet.height);
+
+                Sprites::from_fn(|index, tile| {
+                    let [column, row] = manifest
+                        .tiles
+                        .get(tile)
+                        .copied()
+                        .unwrap_or([index as u32, 0]);
+
+                    sheet.crop(column * size, row * size, size, size)
+                })
+            }
+            None => Sprites::from_fn(|_, tile| {
+                let image = read(&format!("{tile}.png"))?;
+                image.crop(0, 0, image.width, image.height)
+            }),
+        };
+
+        match &sprites {
+            Some(_) => info!(name, "loaded skin"),
+            None => warn!(name, "skin is missing tiles"),
+        }
+
+        sprites
+    }
+}
+
+/// Skins live on disk, which the browser does not have.
+#[cfg(target_arch = "wasm32")]
+mod disk {
+    use super::Skin;
+    use crate::sprites::Sprites;
+
+    pub fn available() -> Vec<Skin> {
+        Vec::new()
+    }
+
+    pub fn load(_name: &str) -> Option<Sprites> {
+        None
+    }
+}
+
+pub use disk::{available, load};
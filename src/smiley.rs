@@ -0,0 +1,89 @@
+//! The face above the board, which reacts to the game and starts a new one
+//! when clicked.
+
+use iced::{
+    Color, Point, Rectangle, Renderer, Size, Theme, Vector, mouse,
+    widget::canvas::{self, Frame, Path, Stroke},
+};
+
+const FACE: Color = Color::from_rgb(1.0, 0.85, 0.1);
+const INK: Color = Color::BLACK;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Face {
+    Smiling,
+    /// A button is held down on the board.
+    Worried,
+    /// Sunglasses, for a cleared board.
+    Cool,
+    Dead,
+}
+
+impl<Message> canvas::Program<Message> for Face {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let side = bounds.width.min(bounds.height);
+
+        // Drawn on a unit square around the centre.
+        frame.translate(Vector::new(bounds.width / 2.0, bounds.height / 2.0));
+        frame.scale(side);
+
+        let stroke = Stroke::default().with_width(0.06).with_color(INK);
+
+        frame.fill(&Path::circle(Point::ORIGIN, 0.45), FACE);
+        frame.stroke(&Path::circle(Point::ORIGIN, 0.45), stroke);
+
+        for x in [-0.15, 0.15] {
+            let eye = Point::new(x, -0.1);
+
+            match self {
+                Face::Smiling | Face::Worried => frame.fill(&Path::circle(eye, 0.05), INK),
+                Face::Cool => frame.fill(
+                    &Path::rectangle(eye - Vector::new(0.11, 0.06), Size::new(0.22, 0.12)),
+                    INK,
+                ),
+                Face::Dead => frame.stroke(
+                    &Path::new(|builder| {
+                        builder.move_to(eye + Vector::new(-0.06, -0.06));
+                        builder.line_to(eye + Vector::new(0.06, 0.06));
+                        builder.move_to(eye + Vector::new(0.06, -0.06));
+                        builder.line_to(eye + Vector::new(-0.06, 0.06));
+                    }),
+                    stroke.with_width(0.04),
+                ),
+            }
+        }
+
+        if *self == Face::Cool {
+            frame.stroke(
+                &Path::line(Point::new(-0.3, -0.12), Point::new(0.3, -0.12)),
+                stroke.with_width(0.04),
+            );
+        }
+
+        let mouth = Path::new(|builder| match self {
+            Face::Smiling | Face::Cool => {
+                builder.move_to(Point::new(-0.2, 0.1));
+                builder.quadratic_curve_to(Point::new(0.0, 0.3), Point::new(0.2, 0.1));
+            }
+            Face::Worried => builder.circle(Point::new(0.0, 0.17), 0.07),
+            Face::Dead => {
+                builder.move_to(Point::new(-0.2, 0.23));
+                builder.quadratic_curve_to(Point::new(0.0, 0.05), Point::new(0.2, 0.23));
+            }
+        });
+
+        frame.stroke(&mouth, stroke.with_width(0.05));
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use minesweeper::board::{Board, CellType, Position};
+
+/// Finds a hidden cell that the revealed numbers prove to be safe. Each
+/// number constrains its hidden neighbours; a constraint is resolved when it
+/// needs no more mines or every cell must be full, and pairs of constraints
+/// where one covers a subset of the other are reduced to their difference.
+/// Flags are ignored since the player may have placed them wrongly. Liar
+/// boards get no help, as the true numbers would give the lies away.
+pub fn find_safe_cell(board: &Board) -> Option<Position> {
+    if board.grid().liar {
+        return None;
+    }
+
+    let capacity = usize::from(board.grid().mines_per_cell);
+    let mut mines = HashSet::new();
+    let mut safe = HashSet::new();
+
+    loop {
+        let constraints = constraints(board, &mines, &safe);
+        let known = mines.len() + safe.len();
+
+        for (unknown, count) in &constraints {
+            resolve(
+                unknown.iter(),
+                unknown.len() * capacity,
+                *count,
+                &mut mines,
+                &mut safe,
+            );
+        }
+
+        for (smaller, smaller_count) in &constraints {
+            for (larger, larger_count) in &constraints {
+                if smaller.len() < larger.len() && smaller.is_subset(larger) {
+                    resolve(
+                        larger.difference(smaller),
+                        (larger.len() - smaller.len()) * capacity,
+                        larger_count.saturating_sub(*smaller_count),
+                        &mut mines,
+                        &mut safe,
+                    );
+                }
+            }
+        }
+
+        if let Some(&position) = safe.iter().min() {
+            return Some(position);
+        }
+
+        if mines.len() + safe.len() == known {
+            return None;
+        }
+    }
+}
+
+fn constraints(
+    board: &Board,
+    mines: &HashSet<Position>,
+    safe: &HashSet<Position>,
+) -> Vec<(BTreeSet<Position>, usize)> {
+    let grid = board.grid();
+
+    board
+        .cells()
+        .filter(|(_, cell)| cell.is_revealed())
+        .filter_map(|(position, cell)| {
+            let CellType::NonMine { neighbours } = cell.cell_type() else {
+                return None;
+            };
+
+            let hidden = grid
+                .neighbours(position)
+                .into_iter()
+                .filter(|&n| board.get(n).is_some_and(|cell| !cell.is_revealed()));
+
+            let mut known_mines = 0;
+            let mut unknown = BTreeSet::new();
+            for n in hidden {
+                if mines.contains(&n) {
+                    known_mines += usize::from(grid.mines_per_cell);
+                } else if !safe.contains(&n) {
+                    unknown.insert(n);
+                }
+            }
+
+            (!unknown.is_empty()).then(|| (unknown, neighbours.saturating_sub(known_mines)))
+        })
+        .collect()
+}
+
+/// Marks `cells` safe when they hold no mines, or as full mines when they
+/// hold as many as they have room for.
+fn resolve<'a>(
+    cells: impl Iterator<Item = &'a Position>,
+    room: usize,
+    count: usize,
+    mines: &mut HashSet<Position>,
+    safe: &mut HashSet<Position>,
+) {
+    if count == 0 {
+        safe.extend(cells);
+    } else if count == room {
+        mines.extend(cells);
+    }
+}
+
+/// Above this many search steps a frontier component is left without
+/// probabilities instead of stalling the interface.
+const MAX_SEARCH_STEPS: usize = 200_000;
+
+/// The number of solutions of one frontier component that place a given
+/// number of mines, and how often each of its cells is a mine among them.
+#[derive(Clone, Debug, Default)]
+struct Solutions {
+    count: f64,
+    per_cell: Vec<f64>,
+}
+
+/// Computes the chance of every hidden cell next to a revealed number being a
+/// mine. The frontier is split into independent components whose solutions
+/// are enumerated exactly, then weighted by the number of ways the remaining
+/// mines fit into the hidden cells away from the frontier. Boards with
+/// several mines per cell are not supported and get no probabilities, nor
+/// do liar boards.
+pub fn mine_probabilities(board: &Board) -> HashMap<Position, f32> {
+    if board.grid().mines_per_cell > 1 || board.grid().liar {
+        return HashMap::new();
+    }
+
+    let mines = board.mines();
+    let constraints = constraints(board, &HashSet::new(), &HashSet::new());
+    let hidden = board
+        .cells()
+        .filter(|(_, cell)| !cell.is_revealed())
+        .count();
+
+    let mut components = Vec::new();
+    let mut assigned = HashSet::new();
+    for (unknown, _) in &constraints {
+        let Some(&start) = unknown.iter().next() else {
+            continue;
+        };
+
+        if assigned.contains(&start) {
+            continue;
+        }
+
+        let component = component(start, &constraints);
+        assigned.extend(component.iter().copied());
+        components.push(component);
+    }
+
+    let frontier = assigned.len();
+    let interior = hidden - frontier;
+
+    let enumerated: Vec<_> = components
+        .into_iter()
+        .filter_map(|component| {
+            let solutions = enumerate(&component, &constraints)?;
+            Some((component, solutions))
+        })
+        .collect();
+
+    // Mines in components that could not be enumerated are treated as if they
+    // were spread over the interior.
+    let skipped = frontier - enumerated.iter().map(|(c, _)| c.len()).sum::<usize>();
+    let free_cells = interior + skipped;
+
+    let most = enumerated.iter().map(|(c, _)| c.len()).sum();
+    let spread = spread_weights(free_cells, mines, most);
+
+    let totals: Vec<Vec<f64>> = enumerated
+        .iter()
+        .map(|(_, solutions)| solutions.iter().map(|s| s.count).collect())
+        .collect();
+
+    let mut probabilities = HashMap::new();
+
+    for (index, (component, solutions)) in enumerated.iter().enumerate() {
+        let others = totals
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != index)
+            .fold(vec![1.0], |product, (_, total)| convolve(&product, total));
+
+        let mut weight = 0.0;
+        let mut per_cell = vec![0.0; component.len()];
+
+        for (own_mines, solution) in solutions.iter().enumerate() {
+            if solution.count == 0.0 {
+                continue;
+            }
+
+            for (other_mines, &ways) in others.iter().enumerate() {
+                let factor = ways * spread[own_mines + other_mines];
+                weight += solution.count * factor;
+                for (sum, &count) in per_cell.iter_mut().zip(&solution.per_cell) {
+                    *sum += count * factor;
+                }
+            }
+        }
+
+        if weight > 0.0 {
+            for (position, sum) in component.iter().zip(per_cell) {
+                probabilities.insert(*position, (sum / weight) as f32);
+            }
+        }
+    }
+
+    probabilities
+}
+
+fn component(start: Position, constraints: &[(BTreeSet<Position>, usize)]) -> Vec<Position> {
+    let mut component = vec![start];
+    let mut seen = HashSet::from([start]);
+    let mut next = 0;
+
+    while let Some(&position) = component.get(next) {
+        next += 1;
+
+        for (unknown, _) in constraints.iter().filter(|(u, _)| u.contains(&position)) {
+            for &other in unknown {
+                if seen.insert(other) {
+                    component.push(other);
+                }
+            }
+        }
+    }
+
+    component
+}
+
+/// Enumerates every mine placement within a component that satisfies all of
+/// its numbers, indexed by the number of mines placed.
+fn enumerate(
+    component: &[Position],
+    constraints: &[(BTreeSet<Position>, usize)],
+) -> Option<Vec<Solutions>> {
+    let index: HashMap<Position, usize> = component
+        .iter()
+        .enumerate()
+        .map(|(i, &position)| (position, i))
+        .collect();
+
+    let relevant: Vec<(Vec<usize>, usize)> = constraints
+        .iter()
+        .filter(|(unknown, _)| unknown.iter().any(|p| index.contains_key(p)))
+        .map(|(unknown, count)| (unknown.iter().map(|p| index[p]).collect(), *count))
+        .collect();
+
+    let mut touching = vec![Vec::new(); component.len()];
+    for (constraint, (cells, _)) in relevant.iter().enumerate() {
+        for &cell in cells {
+            touching[cell].push(constraint);
+        }
+    }
+
+    let mut search = Search {
+        relevant: &relevant,
+        touching: &touching,
+        assignment: vec![None; component.len()],
+        solutions: vec![Solutions::default(); component.len() + 1],
+        steps: 0,
+    };
+
+    search.run(0).then_some(search.solutions)
+}
+
+struct Search<'a> {
+    relevant: &'a [(Vec<usize>, usize)],
+    touching: &'a [Vec<usize>],
+    assignment: Vec<Option<bool>>,
+    solutions: Vec<Solutions>,
+    steps: usize,
+}
+
+impl Search<'_> {
+    fn run(&mut self, cell: usize) -> bool {
+        self.steps += 1;
+        if self.steps > MAX_SEARCH_STEPS {
+            return false;
+        }
+
+        if cell == self.assignment.len() {
+            let mines = self.assignment.iter().filter(|&&a| a == Some(true)).count();
+            let solutions = &mut self.solutions[mines];
+            if solutions.per_cell.is_empty() {
+                solutions.per_cell = vec![0.0; self.assignment.len()];
+            }
+
+            solutions.count += 1.0;
+            for (sum, &mine) in solutions.per_cell.iter_mut().zip(&self.assignment) {
+                if mine == Some(true) {
+                    *sum += 1.0;
+                }
+            }
+
+            return true;
+        }
+
+        for mine in [false, true] {
+            self.assignment[cell] = Some(mine);
+
+            if self.is_consistent(cell) && !self.run(cell + 1) {
+                return false;
+            }
+        }
+
+        self.assignment[cell] = None;
+        true
+    }
+
+    fn is_consistent(&self, cell: usize) -> bool {
+        self.touching[cell].iter().all(|&constraint| {
+            let (cells, count) = &self.relevant[constraint];
+            let mut mines = 0;
+            let mut open = 0;
+
+            for &c in cells {
+                match self.assignment[c] {
+                    Some(true) => mines += 1,
+                    Some(false) => {}
+                    None => open += 1,
+                }
+            }
+
+            mines <= *count && mines + open >= *count
+        })
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; a.len() + b.len() - 1];
+
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+
+    result
+}
+
+/// How many ways the mines left over fit into the `free` cells away from
+/// the frontier, once the frontier holds `t` of them, for every `t` up to
+/// `most`. Only the ratios matter, and the counts themselves overflow on
+/// large boards, so they are worked out in log space from the ratio of
+/// neighbouring binomials and scaled so that the largest is 1.
+fn spread_weights(free: usize, mines: usize, most: usize) -> Vec<f64> {
+    let mut log = None;
+    let logs: Vec<f64> = (0..=most)
+        .map(|t| match mines.checked_sub(t) {
+            Some(rest) if rest <= free => {
+                // C(free, rest) / C(free, rest + 1) = (rest + 1) / (free - rest)
+                let next = log.map_or(0.0, |log: f64| {
+                    log + ((rest + 1) as f64 / (free - rest) as f64).ln()
+                });
+                log = Some(next);
+                next
+            }
+            _ => f64::NEG_INFINITY,
+        })
+        .collect();
+
+    let largest = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    logs.into_iter().map(|log| (log - largest).exp()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use minesweeper::{grid::Grid, seed::Seed};
+
+    use super::*;
+
+    /// A strip two rows high with mines in the top row and every cell of
+    /// the bottom row revealed.
+    fn strip(width: usize, mines: &[usize]) -> Board {
+        let mines: Vec<_> = mines
+            .iter()
+            .map(|&column| Position::new(0, column))
+            .collect();
+        let mut board = Board::with_mines(Grid::new(width, 2), Seed::from_day(0), &mines);
+
+        for column in 0..width {
+            board.reveal(Position::new(1, column));
+        }
+
+        board
+    }
+
+    #[test]
+    fn subsets_prove_cells_safe() {
+        // 1 1 2 1 1 under the top row: the two pairs of ones leave the
+        // middle cell free of mines.
+        let board = strip(5, &[1, 3]);
+
+        assert_eq!(find_safe_cell(&board), Some(Position::new(0, 2)));
+    }
+
+    #[test]
+    fn no_safe_cell_without_proof() {
+        // A single 1 under two hidden cells is a coin toss.
+        let board = strip(2, &[0]);
+
+        assert_eq!(find_safe_cell(&board), None);
+
+        let probabilities = mine_probabilities(&board);
+        assert_eq!(probabilities[&Position::new(0, 0)], 0.5);
+        assert_eq!(probabilities[&Position::new(0, 1)], 0.5);
+    }
+
+    #[test]
+    fn probabilities_stay_finite_on_huge_boards() {
+        let mut board = Board::new(Grid::new(200, 200), 8000, Seed::from_day(5));
+        board.reveal(Position::new(100, 100));
+
+        let probabilities = mine_probabilities(&board);
+
+        assert!(!probabilities.is_empty());
+        assert!(
+            probabilities
+                .values()
+                .all(|p| p.is_finite() && (0.0..=1.0).contains(p))
+        );
+    }
+
+    #[test]
+    fn probabilities_follow_the_deductions() {
+        let probabilities = mine_probabilities(&strip(5, &[1, 3]));
+
+        assert_eq!(probabilities[&Position::new(0, 1)], 1.0);
+        assert_eq!(probabilities[&Position::new(0, 2)], 0.0);
+        assert_eq!(probabilities[&Position::new(0, 3)], 1.0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Sound effects for moves on the board, played on a thread of their own so
+//! that decoding never holds up drawing.
+
+use crate::settings::SoundSettings;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sound {
+    Reveal,
+    Flag,
+    Chord,
+    Explosion,
+    Win,
+}
+
+pub fn play(sound: Sound, settings: &SoundSettings) {
+    if !settings.muted && settings.volume > 0.0 {
+        output::play(sound, settings.volume);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod output {
+    use std::{
+        io::Cursor,
+        sync::{
+            LazyLock,
+            mpsc::{self, Sender},
+        },
+        thread,
+    };
+
+    use rodio::{Decoder, OutputStream, Source};
+    use tracing::{info, warn};
+
+    use super::Sound;
+
+    /// The WAV sample, embedded in the binary.
+    fn sample(sound: Sound) -> &'static [u8] {
+        match sound {
+            Sound::Reveal => include_bytes!("../assets/sounds/reveal.wav"),
+            Sound::Flag => include_bytes!("../assets/sounds/flag.wav"),
+            Sound::Chord => include_bytes!("../assets/sounds/chord.wav"),
+            Sound::Explosion => include_bytes!("../assets/sounds/explosion.wav"),
+            Sound::Win => include_bytes!("../assets/sounds/win.wav"),
+        }
+    }
+
+    /// The audio output is opened the first time a sound plays. The stream
+    /// cannot leave the thread that opened it, so sounds are sent there.
+    static PLAYER: LazyLock<Sender<(Sound, f32)>> = LazyLock::new(|| {
+        let (sender, receiver) = mpsc::channel::<(Sound, f32)>();
+
+        thread::spawn(move || {
+            let (_stream, handle) = match OutputStream::try_default() {
+                Ok(output) => output,
+                Err(error) => {
+                    warn!(%error, "could not open audio output, sounds are off");
+                    return;
+                }
+            };
+            info!("opened audio output");
+
+            for (sound, volume) in receiver {
+                let source = match Decoder::new_wav(Cursor::new(sample(sound))) {
+                    Ok(source) => source,
+                    Err(error) => {
+                        warn!(?sound, %error, "could not decode sound");
+                        continue;
+                    }
+                };
+
+                if let Err(error) = handle.play_raw(source.amplify(volume).convert_samples()) {
+                    warn!(?sound, %error, "could not play sound");
+                }
+            }
+        });
+
+        sender
+    });
+
+    pub fn play(sound: Sound, volume: f32) {
+        // Sending only fails once the output could not be opened, which has
+        // been logged already.
+        let _ = PLAYER.send((sound, volume));
+    }
+}
+
+/// The browser build has no audio output yet.
+#[cfg(target_arch = "wasm32")]
+mod output {
+    use super::Sound;
+
+    pub fn play(_sound: Sound, _volume: f32) {}
+}
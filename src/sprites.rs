@@ -0,0 +1,175 @@
+//! Classic bevelled tiles, cut from an image atlas embedded in the binary or
+//! from a skin the player installed.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use iced::advanced::image::{FilterMethod, Handle, Image};
+use minesweeper::board::{Cell, CellType, Marking};
+use tracing::warn;
+
+use crate::skins::{self, Skin};
+
+/// A row of square tiles in the order of [`TILE_NAMES`].
+const ATLAS: &[u8] = include_bytes!("../assets/tiles.png");
+
+/// The tiles a set of sprites is made of, in the order of the built-in atlas.
+pub const TILE_NAMES: [&str; 13] = [
+    "covered",
+    "revealed",
+    "flag",
+    "question_mark",
+    "mine",
+    "1",
+    "2",
+    "3",
+    "4",
+    "5",
+    "6",
+    "7",
+    "8",
+];
+
+pub struct Sprites {
+    covered: Image,
+    revealed: Image,
+    flag: Image,
+    question_mark: Image,
+    mine: Image,
+    numbers: [Image; 8],
+}
+
+static BUILT_IN: LazyLock<Option<Sprites>> = LazyLock::new(|| {
+    let atlas = Pixels::decode(ATLAS)?;
+    let size = atlas.height;
+
+    Sprites::from_fn(|index, _| atlas.crop(index as u32 * size, 0, size, size))
+});
+
+/// Skins are read from disk the first time they are drawn and kept for as
+/// long as the game runs. Skins that failed to load are remembered as `None`.
+static SKINS: LazyLock<Mutex<HashMap<String, Option<&'static Sprites>>>> =
+    LazyLock::new(Mutex::default);
+
+/// The tiles of a skin, or `None` if they could not be read, in which case
+/// the board is drawn in the vector style.
+pub fn get(skin: &Skin) -> Option<&'static Sprites> {
+    match skin {
+        Skin::BuiltIn => BUILT_IN.as_ref(),
+        Skin::Custom(name) => *SKINS
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .entry(name.clone())
+            .or_insert_with(|| skins::load(name).map(|sprites| &*Box::leak(Box::new(sprites)))),
+    }
+}
+
+impl Sprites {
+    /// Builds the sprites from a tile for each of [`TILE_NAMES`], given with
+    /// its index.
+    pub fn from_fn(mut tile: impl FnMut(usize, &str) -> Option<Image>) -> Option<Self> {
+        let tiles = TILE_NAMES
+            .iter()
+            .enumerate()
+            .map(|(index, name)| tile(index, name))
+            .collect::<Option<Vec<_>>>()?;
+        let tiles: [Image; TILE_NAMES.len()] = tiles.try_into().ok()?;
+        let [covered, revealed, flag, question_mark, mine, numbers @ ..] = tiles;
+
+        Some(Self {
+            covered,
+            revealed,
+            flag,
+            question_mark,
+            mine,
+            numbers,
+        })
+    }
+
+    /// The tile showing a cell, or `None` for the counts that only boards
+    /// with several mines per cell reach, which the atlas has no tiles for.
+    pub fn tile(&self, cell: Cell) -> Option<&Image> {
+        match (cell.is_revealed(), cell.marking(), cell.cell_type()) {
+            (true, _, CellType::Mine { count: 1 }) => Some(&self.mine),
+            (true, _, CellType::Mine { .. }) => None,
+            (true, _, CellType::NonMine { .. }) => match cell.number() {
+                Some(number @ 1..) => self.numbers.get(number - 1),
+                _ => Some(&self.revealed),
+            },
+            (false, Marking::Flag, _) if cell.flags() == 1 => Some(&self.flag),
+            (false, Marking::Flag, _) => None,
+            (false, Marking::QuestionMark, _) => Some(&self.question_mark),
+            (false, Marking::None, _) => Some(&self.covered),
+        }
+    }
+}
+
+/// A decoded image, as rows of RGBA pixels.
+pub struct Pixels {
+    pub width: u32,
+    pub height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Pixels {
+    pub fn decode(png: &[u8]) -> Option<Self> {
+        let mut decoder = png::Decoder::new(png);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder
+            .read_info()
+            .inspect_err(|error| warn!(%error, "could not read image"))
+            .ok()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut pixels)
+            .inspect_err(|error| warn!(%error, "could not decode image"))
+            .ok()?;
+        pixels.truncate(info.buffer_size());
+
+        // Colour types without alpha are widened to RGBA.
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => pixels,
+            png::ColorType::Rgb => pixels
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => pixels
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+                .collect(),
+            png::ColorType::Grayscale => pixels
+                .iter()
+                .flat_map(|&value| [value, value, value, 0xff])
+                .collect(),
+            png::ColorType::Indexed => {
+                warn!("indexed image was not expanded");
+                return None;
+            }
+        };
+
+        Some(Self {
+            width: info.width,
+            height: info.height,
+            rgba,
+        })
+    }
+
+    /// Cuts out a tile, or returns `None` if it reaches past the image.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<Image> {
+        if x + width > self.width || y + height > self.height {
+            warn!(x, y, width, height, "tile lies outside its image");
+            return None;
+        }
+
+        let rows = (y..y + height).flat_map(|row| {
+            let start = ((row * self.width + x) * 4) as usize;
+            &self.rgba[start..start + width as usize * 4]
+        });
+        let handle = Handle::from_rgba(width, height, rows.copied().collect::<Vec<_>>());
+
+        Some(Image::new(handle).filter_method(FilterMethod::Nearest))
+    }
+}
@@ -0,0 +1,267 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use iced::{
+    Element,
+    Length::Fill,
+    widget::{button, column, scrollable, text},
+};
+use minesweeper::difficulty::Difficulty;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    daily::Day,
+    metrics::{Clicks, Metrics},
+    storage,
+    time_attack::Score,
+};
+
+const FILE: &str = "statistics.json";
+
+/// Lifetime results per difficulty, persisted after every finished game.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Statistics {
+    difficulties: BTreeMap<Difficulty, DifficultyStats>,
+    /// Games played without flags, kept apart from the rest.
+    #[serde(default)]
+    no_flags: BTreeMap<Difficulty, DifficultyStats>,
+    #[serde(default)]
+    time_attack: TimeAttackStats,
+    #[serde(default)]
+    daily: DailyStats,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct DifficultyStats {
+    pub played: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub total_win_time: Duration,
+    /// Clicks across every won or lost game.
+    #[serde(default)]
+    pub clicks: Clicks,
+    /// The 3BV cleared in those games, for the overall efficiency.
+    #[serde(default)]
+    pub solved_three_bv: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct TimeAttackStats {
+    pub played: u32,
+    pub best: Score,
+}
+
+/// Results of the daily boards. The streak counts consecutive days won.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct DailyStats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub last_attempt: Option<Day>,
+    pub last_win: Option<Day>,
+}
+
+impl DailyStats {
+    pub fn has_attempted(&self, day: Day) -> bool {
+        self.last_attempt == Some(day)
+    }
+}
+
+impl DifficultyStats {
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.won > 0).then(|| self.total_win_time / self.won)
+    }
+
+    /// The 3BV cleared per hundred clicks, over every finished game.
+    pub fn efficiency(&self) -> Option<f32> {
+        let clicks = self.clicks.total();
+        (clicks > 0).then(|| self.solved_three_bv as f32 / clicks as f32 * 100.0)
+    }
+
+    fn add_clicks(&mut self, metrics: Metrics) {
+        self.clicks += metrics.clicks;
+        self.solved_three_bv += metrics.solved_three_bv;
+    }
+}
+
+impl Statistics {
+    pub fn load() -> Self {
+        storage::load(FILE)
+    }
+
+    pub fn get(&self, difficulty: Difficulty, no_flags: bool) -> DifficultyStats {
+        let difficulties = if no_flags {
+            &self.no_flags
+        } else {
+            &self.difficulties
+        };
+
+        difficulties.get(&difficulty).copied().unwrap_or_default()
+    }
+
+    fn entry(&mut self, difficulty: Difficulty, no_flags: bool) -> &mut DifficultyStats {
+        let difficulties = if no_flags {
+            &mut self.no_flags
+        } else {
+            &mut self.difficulties
+        };
+
+        difficulties.entry(difficulty).or_default()
+    }
+
+    pub fn time_attack(&self) -> TimeAttackStats {
+        self.time_attack
+    }
+
+    pub fn record_time_attack(&mut self, score: Score) {
+        self.time_attack.played += 1;
+        self.time_attack.best = self.time_attack.best.max(score);
+
+        storage::save(FILE, self);
+    }
+
+    pub fn daily(&self) -> DailyStats {
+        self.daily
+    }
+
+    /// Uses up the attempt for `day`. This is saved before the result so that
+    /// quitting a daily board does not allow another try.
+    pub fn record_daily_attempt(&mut self, day: Day) {
+        let daily = &mut self.daily;
+        daily.played += 1;
+        daily.last_attempt = Some(day);
+        if !daily.last_win.is_some_and(|last_win| day.follows(last_win)) {
+            daily.current_streak = 0;
+        }
+
+        storage::save(FILE, self);
+    }
+
+    pub fn record_daily(&mut self, day: Day, won: bool) {
+        let daily = &mut self.daily;
+        if won {
+            daily.won += 1;
+            daily.current_streak += 1;
+            daily.best_streak = daily.best_streak.max(daily.current_streak);
+            daily.last_win = Some(day);
+        } else {
+            daily.current_streak = 0;
+        }
+
+        storage::save(FILE, self);
+    }
+
+    pub fn record_win(&mut self, difficulty: Difficulty, no_flags: bool, metrics: Metrics) {
+        let stats = self.entry(difficulty, no_flags);
+        stats.played += 1;
+        stats.won += 1;
+        stats.current_streak += 1;
+        stats.best_streak = stats.best_streak.max(stats.current_streak);
+        stats.total_win_time += metrics.elapsed;
+        stats.add_clicks(metrics);
+
+        storage::save(FILE, self);
+    }
+
+    pub fn record_loss(&mut self, difficulty: Difficulty, no_flags: bool, metrics: Metrics) {
+        let stats = self.entry(difficulty, no_flags);
+        stats.played += 1;
+        stats.lost += 1;
+        stats.current_streak = 0;
+        stats.add_clicks(metrics);
+
+        storage::save(FILE, self);
+    }
+
+    /// Counts a game that was left before it was decided; it breaks the
+    /// streak but is neither a win nor a loss.
+    pub fn record_abandoned(&mut self, difficulty: Difficulty, no_flags: bool) {
+        let stats = self.entry(difficulty, no_flags);
+        stats.played += 1;
+        stats.current_streak = 0;
+
+        storage::save(FILE, self);
+    }
+
+    pub fn view<'a, Message: Clone + 'a>(&self, on_close: Message) -> Element<'a, Message> {
+        // Non-flagging results only show up once there are some.
+        let difficulties = Difficulty::ALL
+            .into_iter()
+            .flat_map(|difficulty| [(difficulty, false), (difficulty, true)])
+            .filter(|&(difficulty, no_flags)| !no_flags || self.get(difficulty, true).played > 0)
+            .map(|(difficulty, no_flags)| {
+                let stats = self.get(difficulty, no_flags);
+                let win_rate = match stats.played {
+                    0 => 0.0,
+                    played => stats.won as f32 / played as f32 * 100.0,
+                };
+                let average = match stats.average_time() {
+                    Some(time) => format!("{:.1} s", time.as_secs_f32()),
+                    None => "-".to_owned(),
+                };
+                let efficiency = match stats.efficiency() {
+                    Some(efficiency) => format!("{efficiency:.0}%"),
+                    None => "-".to_owned(),
+                };
+
+                column![
+                    text!("{difficulty:?}{}", if no_flags { " (NF)" } else { "" }).size(18),
+                    text!("Played: {}", stats.played).size(14),
+                    text!("Won: {} ({win_rate:.0}%)", stats.won).size(14),
+                    text!("Lost: {}", stats.lost).size(14),
+                    text!(
+                        "Streak: {} (best {})",
+                        stats.current_streak,
+                        stats.best_streak
+                    )
+                    .size(14),
+                    text!("Average time: {average}").size(14),
+                    text!("Clicks: {}", stats.clicks).size(14),
+                    text!("Efficiency: {efficiency}").size(14),
+                ]
+                .spacing(4)
+                .into()
+            });
+
+        let time_attack = column![
+            text("Time attack").size(18),
+            text!("Played: {}", self.time_attack.played).size(14),
+            text!(
+                "Best: {} boards, {} cells",
+                self.time_attack.best.boards,
+                self.time_attack.best.cells
+            )
+            .size(14),
+        ]
+        .spacing(4);
+
+        let daily = column![
+            text("Daily").size(18),
+            text!("Played: {}", self.daily.played).size(14),
+            text!("Won: {}", self.daily.won).size(14),
+            text!(
+                "Streak: {} (best {})",
+                self.daily.current_streak,
+                self.daily.best_streak
+            )
+            .size(14),
+        ]
+        .spacing(4);
+
+        scrollable(
+            column(difficulties)
+                .push(time_attack)
+                .push(daily)
+                .push(
+                    button(text("Back").center().width(Fill))
+                        .on_press(on_close)
+                        .width(Fill),
+                )
+                .padding(24)
+                .spacing(12),
+        )
+        .into()
+    }
+}
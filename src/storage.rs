@@ -0,0 +1,118 @@
+use serde::{Serialize, de::DeserializeOwned};
+use tracing::warn;
+
+/// Files live in the platform's config directory.
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::{fs, io, path::PathBuf};
+
+    pub fn directory() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("minesweeper"))
+    }
+
+    fn path(file: &str) -> Option<PathBuf> {
+        Some(directory()?.join(file))
+    }
+
+    pub fn read(file: &str) -> Option<String> {
+        fs::read_to_string(path(file)?).ok()
+    }
+
+    pub fn write(file: &str, contents: &str) -> io::Result<()> {
+        let Some(path) = path(file) else {
+            return Ok(());
+        };
+
+        path.parent().map_or(Ok(()), fs::create_dir_all)?;
+        fs::write(path, contents)
+    }
+
+    pub fn remove(file: &str) -> io::Result<()> {
+        match path(file).map(fs::remove_file) {
+            Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Files are kept in the browser's local storage, one entry per file.
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use std::io;
+
+    use web_sys::Storage;
+
+    fn storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn key(file: &str) -> String {
+        format!("minesweeper/{file}")
+    }
+
+    pub fn read(file: &str) -> Option<String> {
+        storage()?.get_item(&key(file)).ok()?
+    }
+
+    pub fn write(file: &str, contents: &str) -> io::Result<()> {
+        let Some(storage) = storage() else {
+            return Ok(());
+        };
+
+        storage
+            .set_item(&key(file), contents)
+            .map_err(|error| io::Error::other(format!("{error:?}")))
+    }
+
+    pub fn remove(file: &str) -> io::Result<()> {
+        let Some(storage) = storage() else {
+            return Ok(());
+        };
+
+        storage
+            .remove_item(&key(file))
+            .map_err(|error| io::Error::other(format!("{error:?}")))
+    }
+}
+
+/// The directory files are kept in, for the files players add themselves.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn config_dir() -> Option<std::path::PathBuf> {
+    backend::directory()
+}
+
+/// Reads a JSON file, falling back to the default value when it is missing
+/// or unreadable.
+pub fn load<T: DeserializeOwned + Default>(file: &str) -> T {
+    match read(file) {
+        Some(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+            warn!(file, %error, "could not parse file");
+            T::default()
+        }),
+        None => T::default(),
+    }
+}
+
+pub fn save<T: Serialize>(file: &str, value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(contents) => write(file, &contents),
+        Err(error) => warn!(file, %error, "could not save file"),
+    }
+}
+
+/// Reads a file in whatever format its owner keeps it.
+pub fn read(file: &str) -> Option<String> {
+    backend::read(file)
+}
+
+pub fn write(file: &str, contents: &str) {
+    if let Err(error) = backend::write(file, contents) {
+        warn!(file, %error, "could not save file");
+    }
+}
+
+pub fn remove(file: &str) {
+    if let Err(error) = backend::remove(file) {
+        warn!(file, %error, "could not remove file");
+    }
+}
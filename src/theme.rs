@@ -0,0 +1,176 @@
+use std::fmt;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+/// The colours the board is drawn in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardTheme {
+    /// Behind the board, where it does not fill the canvas.
+    pub background: Color,
+    pub covered: Color,
+    pub revealed: Color,
+    pub flag: Color,
+    pub question_mark: Color,
+    pub mine: Color,
+    /// The glyphs of mines, flags and question marks.
+    pub glyph: Color,
+    pub numbers: NumberPalette,
+    pub hover: Color,
+    pub pressed: Color,
+    /// Cells revealed by the last move, faded out over time.
+    pub highlight: Color,
+    pub keyboard_cursor: Color,
+    pub hint: Color,
+    /// The mine that ended the game.
+    pub fatal: Color,
+    pub wrong_flag: Color,
+}
+
+/// The built-in themes, as chosen in the settings.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Appearance {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl Appearance {
+    pub const ALL: [Appearance; 3] = [
+        Appearance::Dark,
+        Appearance::Light,
+        Appearance::HighContrast,
+    ];
+
+    pub fn theme(self) -> BoardTheme {
+        match self {
+            Appearance::Dark => BoardTheme {
+                background: Color::from_rgb8(0x20, 0x20, 0x20),
+                covered: Color::from_rgb8(0x40, 0x40, 0x40),
+                revealed: Color::from_rgb8(0xff, 0xff, 0xff),
+                flag: Color::from_rgb8(0xff, 0x30, 0x10),
+                question_mark: Color::from_rgb8(0x20, 0x80, 0x40),
+                mine: Color::from_rgb8(0xff, 0x00, 0x00),
+                glyph: Color::BLACK,
+                numbers: NumberPalette::default(),
+                hover: Color::from_rgba8(0xff, 0xff, 0xff, 0.5),
+                pressed: Color::from_rgb8(0x10, 0x10, 0x10),
+                highlight: Color::from_rgba8(0xff, 0xc0, 0x20, 0.5),
+                keyboard_cursor: Color::from_rgb8(0x20, 0x80, 0xff),
+                hint: Color::from_rgba8(0x40, 0xd0, 0x40, 0.6),
+                fatal: Color::from_rgb8(0xff, 0x20, 0x20),
+                wrong_flag: Color::from_rgb8(0xc0, 0x00, 0x00),
+            },
+            Appearance::Light => BoardTheme {
+                background: Color::from_rgb8(0xe8, 0xe8, 0xe8),
+                covered: Color::from_rgb8(0xa8, 0xa8, 0xa8),
+                revealed: Color::from_rgb8(0xff, 0xff, 0xff),
+                flag: Color::from_rgb8(0xff, 0x60, 0x40),
+                question_mark: Color::from_rgb8(0x60, 0xc0, 0x80),
+                mine: Color::from_rgb8(0xff, 0x40, 0x40),
+                glyph: Color::BLACK,
+                numbers: NumberPalette::default(),
+                hover: Color::from_rgba8(0xff, 0xff, 0xff, 0.6),
+                pressed: Color::from_rgb8(0x80, 0x80, 0x80),
+                highlight: Color::from_rgba8(0xff, 0xc0, 0x20, 0.4),
+                keyboard_cursor: Color::from_rgb8(0x20, 0x60, 0xe0),
+                hint: Color::from_rgba8(0x40, 0xc0, 0x40, 0.6),
+                fatal: Color::from_rgb8(0xe0, 0x00, 0x00),
+                wrong_flag: Color::from_rgb8(0xa0, 0x00, 0x00),
+            },
+            Appearance::HighContrast => BoardTheme {
+                background: Color::BLACK,
+                covered: Color::from_rgb8(0x60, 0x60, 0x60),
+                revealed: Color::WHITE,
+                flag: Color::from_rgb8(0xff, 0xff, 0x00),
+                question_mark: Color::from_rgb8(0x00, 0xff, 0xff),
+                mine: Color::from_rgb8(0xff, 0x00, 0x00),
+                glyph: Color::BLACK,
+                numbers: NumberPalette([
+                    Color::from_rgb8(0x00, 0x00, 0xe0),
+                    Color::from_rgb8(0x00, 0x60, 0x00),
+                    Color::from_rgb8(0xc0, 0x00, 0x00),
+                    Color::from_rgb8(0x00, 0x00, 0x70),
+                    Color::from_rgb8(0x70, 0x00, 0x00),
+                    Color::from_rgb8(0x00, 0x58, 0x58),
+                    Color::BLACK,
+                    Color::from_rgb8(0x40, 0x40, 0x40),
+                ]),
+                hover: Color::from_rgba8(0xff, 0xff, 0x00, 0.6),
+                pressed: Color::BLACK,
+                highlight: Color::from_rgba8(0xff, 0xa0, 0x00, 0.6),
+                keyboard_cursor: Color::from_rgb8(0x00, 0xa0, 0xff),
+                hint: Color::from_rgba8(0x00, 0xff, 0x00, 0.7),
+                fatal: Color::from_rgb8(0xff, 0x00, 0xff),
+                wrong_flag: Color::from_rgb8(0xff, 0x00, 0xff),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Appearance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Appearance::Dark => "Dark",
+            Appearance::Light => "Light",
+            Appearance::HighContrast => "High contrast",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// How cells are drawn.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub enum TileStyle {
+    /// Bevelled tiles, taken from the sprite atlas on square boards.
+    #[default]
+    Classic,
+    /// Flat shapes in the colours of the theme.
+    Flat,
+}
+
+impl TileStyle {
+    pub const ALL: [TileStyle; 2] = [TileStyle::Classic, TileStyle::Flat];
+}
+
+impl fmt::Display for TileStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TileStyle::Classic => "Classic",
+            TileStyle::Flat => "Flat",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// The colours of the numbers on revealed cells, starting with one
+/// neighbouring mine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumberPalette(pub [Color; 8]);
+
+impl NumberPalette {
+    /// The colour of a number of neighbouring mines. Counts past eight, which
+    /// only boards with several mines per cell reach, take the last colour.
+    pub fn color(&self, neighbours: usize) -> Color {
+        self.0[neighbours.clamp(1, self.0.len()) - 1]
+    }
+}
+
+/// The colours of the original game.
+impl Default for NumberPalette {
+    fn default() -> Self {
+        Self([
+            Color::from_rgb8(0x00, 0x00, 0xff),
+            Color::from_rgb8(0x00, 0x80, 0x00),
+            Color::from_rgb8(0xff, 0x00, 0x00),
+            Color::from_rgb8(0x00, 0x00, 0x80),
+            Color::from_rgb8(0x80, 0x00, 0x00),
+            Color::from_rgb8(0x00, 0x80, 0x80),
+            Color::from_rgb8(0x00, 0x00, 0x00),
+            Color::from_rgb8(0x80, 0x80, 0x80),
+        ])
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::timer::Timer;
+
+/// How long a time attack run lasts.
+pub const BUDGET: Duration = Duration::from_secs(180);
+
+/// A run of boards played against a single clock. Finishing a board, won or
+/// lost, moves straight on to the next one.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeAttack {
+    timer: Timer,
+    score: Score,
+}
+
+/// Boards cleared rank first; cleared cells, including those of boards that
+/// were lost, break ties.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub struct Score {
+    pub boards: usize,
+    pub cells: usize,
+}
+
+impl TimeAttack {
+    pub fn new(now: Instant) -> Self {
+        let mut timer = Timer::default();
+        timer.start(now);
+
+        Self {
+            timer,
+            score: Score::default(),
+        }
+    }
+
+    pub fn tick(&mut self, now: Instant) {
+        self.timer.tick(now);
+    }
+
+    /// Stops the clock while the board is paused and starts it again once
+    /// play resumes.
+    pub fn set_paused(&mut self, paused: bool, now: Instant) {
+        if paused {
+            self.timer.stop(now);
+        } else {
+            self.timer.start(now);
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        BUDGET.saturating_sub(self.timer.elapsed())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    pub fn finish_board(&mut self, won: bool, cells: usize) {
+        self.score.boards += usize::from(won);
+        self.score.cells += cells;
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// Measures the playing time of a game. The elapsed time is only brought up
+/// to date by [`Timer::tick`] and [`Timer::stop`], so it can be read without
+/// consulting the clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timer {
+    resumed_at: Option<Instant>,
+    accumulated: Duration,
+    elapsed: Duration,
+}
+
+impl Timer {
+    /// A stopped timer that already shows `elapsed`, for resumed games.
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        Self {
+            resumed_at: None,
+            accumulated: elapsed,
+            elapsed,
+        }
+    }
+
+    pub fn start(&mut self, now: Instant) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(now);
+        }
+    }
+
+    pub fn stop(&mut self, now: Instant) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.accumulated += now - resumed_at;
+            self.elapsed = self.accumulated;
+        }
+    }
+
+    pub fn tick(&mut self, now: Instant) {
+        if let Some(resumed_at) = self.resumed_at {
+            self.elapsed = self.accumulated + now.saturating_duration_since(resumed_at);
+        }
+    }
+
+    pub fn add_penalty(&mut self, penalty: Duration) {
+        self.accumulated += penalty;
+        self.elapsed += penalty;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.resumed_at.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
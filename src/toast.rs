@@ -0,0 +1,74 @@
+//! Short notices that slide in at the top right and leave by themselves.
+//! Notices queue up and show one at a time.
+
+use std::{collections::VecDeque, time::Duration};
+
+use iced::{
+    Element,
+    Length::Fill,
+    Subscription,
+    widget::{container, horizontal_space, row, stack, text},
+    window,
+};
+use web_time::Instant;
+
+const DURATION: Duration = Duration::from_millis(2500);
+const SLIDE_DURATION: Duration = Duration::from_millis(200);
+/// Notices are cut off at this width, and reach it as they finish
+/// sliding in.
+const MAX_WIDTH: f32 = 280.0;
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    queue: VecDeque<String>,
+    /// When the notice at the front of the queue appeared.
+    shown_at: Option<Instant>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.queue.push_back(message.into());
+    }
+
+    /// Retires the current notice once it has been up long enough.
+    pub fn tick(&mut self, now: Instant) {
+        let shown_at = *self.shown_at.get_or_insert(now);
+
+        if now.saturating_duration_since(shown_at) >= DURATION {
+            self.queue.pop_front();
+            self.shown_at = (!self.queue.is_empty()).then_some(now);
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Instant> {
+        if self.queue.is_empty() {
+            Subscription::none()
+        } else {
+            window::frames()
+        }
+    }
+
+    /// Shows the current notice over `base`.
+    pub fn view<'a, Message: 'a>(&'a self, base: Element<'a, Message>) -> Element<'a, Message> {
+        let toast = self.queue.front().map(|message| {
+            let elapsed = self
+                .shown_at
+                .map_or(Duration::ZERO, |shown_at| shown_at.elapsed());
+            let progress = (elapsed.as_secs_f32() / SLIDE_DURATION.as_secs_f32()).min(1.0);
+
+            // Widening a clipped box against the right edge makes the notice
+            // appear to slide in from beyond it.
+            let toast = container(
+                container(text(message).size(14).wrapping(text::Wrapping::None))
+                    .style(container::rounded_box)
+                    .padding([6, 12]),
+            )
+            .max_width(progress * MAX_WIDTH)
+            .clip(true);
+
+            row![horizontal_space(), toast].width(Fill).padding(8)
+        });
+
+        stack![base].push_maybe(toast).into()
+    }
+}
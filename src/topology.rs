@@ -0,0 +1,155 @@
+use std::ops::Deref;
+
+use iced::{Point, Size, widget::canvas::path::Builder};
+use minesweeper::{board::Position, grid::Shape};
+
+/// The corners of a cell, kept inline since no cell has more than six.
+/// Derefs to a slice of them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Corners {
+    points: [Point; 6],
+    len: usize,
+}
+
+impl Deref for Corners {
+    type Target = [Point];
+
+    fn deref(&self) -> &[Point] {
+        &self.points[..self.len]
+    }
+}
+
+impl FromIterator<Point> for Corners {
+    fn from_iter<I: IntoIterator<Item = Point>>(points: I) -> Self {
+        let mut corners = Self::default();
+
+        for point in points {
+            corners.points[corners.len] = point;
+            corners.len += 1;
+        }
+
+        corners
+    }
+}
+
+/// Where the cells of a [`Shape`] are drawn and which cell a point falls in.
+/// Geometry is in board units, where a cell is one unit wide.
+pub trait Topology {
+    /// The extent of a board with this many columns and rows.
+    fn size(&self, columns: usize, rows: usize) -> Size;
+
+    fn centre(&self, position: Position) -> Point;
+
+    /// The corners of a cell, clockwise as drawn.
+    fn corners(&self, position: Position) -> Corners;
+
+    fn outline(&self, builder: &mut Builder, position: Position) {
+        let corners = self.corners(position);
+
+        builder.move_to(corners[0]);
+        for &corner in &corners[1..] {
+            builder.line_to(corner);
+        }
+        builder.close();
+    }
+
+    /// The cell containing `point`, which may be off the board.
+    fn position_at(&self, point: Point) -> Position;
+}
+
+/// The geometry of cells of the given shape.
+pub fn of(shape: Shape) -> &'static dyn Topology {
+    match shape {
+        Shape::Square => &Square,
+        Shape::Hex => &Hex,
+    }
+}
+
+/// The classic grid of squares.
+struct Square;
+
+impl Topology for Square {
+    fn size(&self, columns: usize, rows: usize) -> Size {
+        Size::new(columns as f32, rows as f32)
+    }
+
+    fn centre(&self, position: Position) -> Point {
+        Point::new(position.column() as f32 + 0.5, position.row() as f32 + 0.5)
+    }
+
+    fn corners(&self, position: Position) -> Corners {
+        let (x, y) = (position.column() as f32, position.row() as f32);
+
+        [
+            Point::new(x, y),
+            Point::new(x + 1.0, y),
+            Point::new(x + 1.0, y + 1.0),
+            Point::new(x, y + 1.0),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn position_at(&self, point: Point) -> Position {
+        Position::default().offset(point.y.floor() as i32, point.x.floor() as i32)
+    }
+}
+
+/// Pointy-topped hexagons. Odd rows are shifted half a cell to the right.
+struct Hex;
+
+/// The distance from a hexagon's centre to its corners.
+const HEX_RADIUS: f32 = 0.577_350_3;
+const HEX_ROW_HEIGHT: f32 = HEX_RADIUS * 1.5;
+
+impl Hex {
+    fn shift(row: i32) -> i32 {
+        row.rem_euclid(2)
+    }
+}
+
+impl Topology for Hex {
+    fn size(&self, columns: usize, rows: usize) -> Size {
+        Size::new(
+            columns as f32 + if rows > 1 { 0.5 } else { 0.0 },
+            rows.saturating_sub(1) as f32 * HEX_ROW_HEIGHT + 2.0 * HEX_RADIUS,
+        )
+    }
+
+    fn centre(&self, position: Position) -> Point {
+        Point::new(
+            position.column() as f32 + 0.5 + Self::shift(position.row()) as f32 / 2.0,
+            position.row() as f32 * HEX_ROW_HEIGHT + HEX_RADIUS,
+        )
+    }
+
+    fn corners(&self, position: Position) -> Corners {
+        let centre = self.centre(position);
+
+        (0..6)
+            .map(|i| {
+                let angle = std::f32::consts::FRAC_PI_3 * i as f32 + std::f32::consts::FRAC_PI_6;
+                Point::new(
+                    centre.x + HEX_RADIUS * angle.cos(),
+                    centre.y + HEX_RADIUS * angle.sin(),
+                )
+            })
+            .collect()
+    }
+
+    /// Picks the nearest centre, which is the hexagon containing the point.
+    fn position_at(&self, point: Point) -> Position {
+        let row = (point.y / HEX_ROW_HEIGHT).floor() as i32;
+        let column = point.x.floor() as i32;
+
+        (row - 1..=row + 1)
+            .flat_map(|row| (column - 1..=column + 1).map(move |column| (row, column)))
+            .map(|(row, column)| Position::default().offset(row, column))
+            .min_by(|a, b| {
+                self.centre(*a)
+                    .distance(point)
+                    .total_cmp(&self.centre(*b).distance(point))
+            })
+            .unwrap_or_default()
+    }
+}
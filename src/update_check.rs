@@ -0,0 +1,78 @@
+use std::thread;
+
+use iced::futures::channel::oneshot;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/JakobStaudinger/minesweeper/releases/latest";
+
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub version: String,
+    pub notes: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    body: Option<String>,
+}
+
+/// Asks GitHub for the latest release and returns it if it is newer than the
+/// running version. Any failure is treated as "no update".
+pub async fn latest_release() -> Option<Release> {
+    let (sender, receiver) = oneshot::channel();
+
+    // The request blocks, so it runs on a thread of its own rather than
+    // holding up the executor.
+    thread::spawn(move || {
+        let _ = sender.send(fetch_latest_release());
+    });
+
+    receiver.await.ok().flatten()
+}
+
+fn fetch_latest_release() -> Option<Release> {
+    let release: GithubRelease = ureq::get(LATEST_RELEASE_URL)
+        .set("User-Agent", "minesweeper")
+        .call()
+        .inspect_err(|error| warn!(%error, "update check failed"))
+        .ok()?
+        .into_json()
+        .inspect_err(|error| warn!(%error, "update check returned an invalid release"))
+        .ok()?;
+
+    let version = release.tag_name.trim_start_matches('v').to_owned();
+    info!(%version, "latest release");
+
+    is_newer(&version, env!("CARGO_PKG_VERSION")).then(|| Release {
+        version,
+        notes: release.body.unwrap_or_default(),
+    })
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+
+    parse(candidate) > parse(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_by_number() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("0.10.0", "0.9.0"));
+        assert!(is_newer("1.0.0", "0.99.99"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.2.0"));
+    }
+}